@@ -0,0 +1,635 @@
+use std::{
+  fs::{File, create_dir_all},
+  io::{Read, Write},
+  path::{Path, PathBuf, Component},
+};
+use compress_tools::{ArchiveIterator, ArchiveContents, list_archive_files};
+use infer;
+
+use crate::installer::{InstallError, InstallProgress, ProgressReporter};
+
+/**
+ * Report extraction progress at most once per this many archive entries.
+ */
+const REPORT_ENTRIES: u64 = 25;
+
+const FILE_TYPE_MASK: u32 = 0o170000;
+const DIRECTORY: u32 = 0o040000;
+const REGULAR_FILE: u32 = 0o100000;
+const SYMLINK: u32 = 0o120000;
+
+/**
+ * Extraction stops once this many bytes have been written, so a malicious or broken archive can't fill up the drive.
+ * The biggest mods, such as graphics packs, are a few gigabytes.
+ */
+const MAX_EXTRACTED_BYTES: u64 = 16 * 1024 * 1024 * 1024;
+/**
+ * Extraction stops after this many entries, for the same reason.
+ */
+const MAX_ENTRIES: u64 = 200_000;
+/**
+ * Longer link targets than this are cut short, since they are only read to check where they point.
+ */
+const MAX_LINK_TARGET: usize = 4096;
+
+/**
+ * Every archive format the installer understands, in the order they're tried.
+ */
+static FORMATS: &[&dyn ArchiveFormat] = &[
+  &Zip,
+  &SevenZip,
+  &Rar,
+  &Tar(TarCompression::None),
+  &Tar(TarCompression::Gzip),
+  &Tar(TarCompression::Xz),
+  &Tar(TarCompression::Bzip2),
+];
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum EntryKind {
+  File,
+  Directory,
+  /**
+   * Its data is the path it points to. Links are never extracted.
+   */
+  Symlink,
+  /**
+   * Devices and anything else that isn't a plain file or folder. These are never extracted.
+   */
+  Other,
+}
+
+/**
+ * An entry as listed in an archive. `size` is the uncompressed size the archive claims, which isn't necessarily what it holds.
+ */
+#[derive(Debug, Clone)]
+pub struct EntryHeader {
+  pub name: String,
+  pub kind: EntryKind,
+  pub size: u64,
+}
+
+/**
+ * Receives the entries of an archive in order, each as its header, then its data in chunks, then its end.
+ * Returning an error from any of these stops reading.
+ */
+pub trait EntryVisitor {
+  fn start(&mut self, header: EntryHeader) -> Result<(), InstallError>;
+  fn data(&mut self, chunk: &[u8]) -> Result<(), InstallError>;
+  fn end(&mut self) -> Result<(), InstallError>;
+}
+
+/**
+ * A kind of archive the installer can read and extract. Extracting defaults to reading the archive into the destination
+ * entry by entry, so formats only need to override it if they have a better way.
+ */
+pub trait ArchiveFormat: Sync {
+  fn name(&self) -> &'static str;
+
+  /**
+   * Whether a file is in this format, going by the mime type sniffed from its contents, if any, or else its lowercase file name.
+   */
+  fn matches(&self, mime: Option<&str>, file_name: &str) -> bool;
+
+  /**
+   * Reads every entry of the archive at `path` into `visitor`, without writing anything to disk.
+   */
+  fn read(&self, path: &Path, visitor: &mut dyn EntryVisitor) -> Result<(), InstallError>;
+
+  /**
+   * How many entries the archive at `path` holds, if that's quick to find out. Only used to report progress.
+   */
+  fn count(&self, _path: &Path) -> Option<u64> {
+    None
+  }
+
+  /**
+   * Extracts the archive at `path` into `dest`, which must already exist.
+   */
+  fn extract(&self, path: &Path, dest: &Path, progress: &ProgressReporter) -> Result<(), InstallError> {
+    let total = self.count(path);
+    let mut extractor = Extractor::new(dest, progress, total);
+    progress.report(InstallProgress::Extracting { entries: 0, total });
+
+    self.read(path, &mut extractor)?;
+    progress.report(InstallProgress::Extracting { entries: extractor.entries, total: Some(total.unwrap_or(extractor.entries)) });
+
+    Ok(())
+  }
+}
+
+/**
+ * Works out which format the file at `path` is in.
+ */
+pub fn format_of(path: &Path) -> Result<&'static dyn ArchiveFormat, InstallError> {
+  let mime = infer::get_from_path(path)
+    .map_err(|source| InstallError::Io { source })?
+    .map(|kind| kind.mime_type());
+  let file_name = path.file_name().map(|name| name.to_string_lossy().to_lowercase()).unwrap_or_default();
+
+  FORMATS.iter()
+    .copied()
+    .find(|format| format.matches(mime, &file_name))
+    .ok_or_else(|| InstallError::UnsupportedArchive {
+      detail: match mime {
+        Some(mime) => format!("{} is a {} file, which is not a supported archive format", file_name, mime),
+        None => format!("{} is not in a recognised archive format", file_name)
+      }
+    })
+}
+
+/**
+ * Sniffed mime types are trusted over extensions, since downloads and cached archives don't keep their original names.
+ */
+fn matches_any(mime: Option<&str>, file_name: &str, mimes: &[&str], extensions: &[&str]) -> bool {
+  match mime {
+    Some(mime) => mimes.contains(&mime),
+    None => extensions.iter().any(|extension| file_name.ends_with(extension))
+  }
+}
+
+/**
+ * Checks that an entry's name is a relative path staying inside the folder the archive is extracted to, returning it as one.
+ */
+fn entry_path(name: &str) -> Result<PathBuf, InstallError> {
+  let relative = Path::new(name);
+  if relative.components().any(|component| !matches!(component, Component::Normal(_) | Component::CurDir)) {
+    return Err(InstallError::UnsafeArchive { detail: format!("entry `{}` would be extracted outside of the mod folder", name) })
+  }
+
+  Ok(relative.to_path_buf())
+}
+
+/**
+ * Whether a link at `link`, relative to the extraction folder, pointing to `target` leads outside of the extraction folder.
+ * Only the paths themselves are looked at, since nothing they point to might exist yet.
+ */
+fn link_escapes(link: &Path, target: &Path) -> bool {
+  let mut resolved: Vec<Component> = link.parent().map_or_else(Vec::new, |parent| parent.components().collect());
+  for component in target.components() {
+    match component {
+      Component::Normal(_) => resolved.push(component),
+      Component::CurDir => {},
+      Component::ParentDir => if resolved.pop().is_none() {
+        return true
+      },
+      Component::RootDir | Component::Prefix(_) => return true
+    }
+  }
+
+  false
+}
+
+/**
+ * Writes files and folders into `dest` as they're read. Entries that would land outside of it, links pointing outside of it
+ * and archives that grow too large while extracting are refused with `InstallError::UnsafeArchive`.
+ */
+struct Extractor<'a> {
+  dest: &'a Path,
+  progress: &'a ProgressReporter,
+  total: Option<u64>,
+  current: Option<File>,
+  link: Option<(PathBuf, Vec<u8>)>,
+  entries: u64,
+  written: u64,
+}
+
+impl<'a> Extractor<'a> {
+  fn new(dest: &'a Path, progress: &'a ProgressReporter, total: Option<u64>) -> Self {
+    Extractor {
+      dest,
+      progress,
+      total,
+      current: None,
+      link: None,
+      entries: 0,
+      written: 0,
+    }
+  }
+
+  fn too_large(&self) -> InstallError {
+    InstallError::UnsafeArchive { detail: format!("it extracts to more than {} GB", MAX_EXTRACTED_BYTES / (1024 * 1024 * 1024)) }
+  }
+
+  fn check_cancelled(&self) -> Result<(), InstallError> {
+    if self.progress.is_cancelled() {
+      Err(InstallError::Cancelled)
+    } else {
+      Ok(())
+    }
+  }
+}
+
+impl EntryVisitor for Extractor<'_> {
+  fn start(&mut self, header: EntryHeader) -> Result<(), InstallError> {
+    self.check_cancelled()?;
+
+    if self.entries >= MAX_ENTRIES {
+      return Err(InstallError::UnsafeArchive { detail: format!("it holds more than {} files", MAX_ENTRIES) })
+    }
+    // Sizes can be faked, so this only catches archives that admit to being too large. What's written is checked as it's written
+    if header.kind == EntryKind::File && self.written.saturating_add(header.size) > MAX_EXTRACTED_BYTES {
+      return Err(self.too_large())
+    }
+
+    let relative = entry_path(&header.name)?;
+    let target = self.dest.join(&relative);
+    self.link = None;
+    self.current = match header.kind {
+      EntryKind::Directory => {
+        create_dir_all(&target).map_err(|source| InstallError::Io { source })?;
+
+        None
+      },
+      EntryKind::File => {
+        if let Some(parent) = target.parent() {
+          create_dir_all(parent).map_err(|source| InstallError::Io { source })?;
+        }
+
+        Some(File::create(&target).map_err(|source| InstallError::Io { source })?)
+      },
+      EntryKind::Symlink => {
+        self.link = Some((relative, vec![]));
+
+        None
+      },
+      EntryKind::Other => None
+    };
+
+    Ok(())
+  }
+
+  fn data(&mut self, chunk: &[u8]) -> Result<(), InstallError> {
+    self.check_cancelled()?;
+    if let Some(file) = self.current.as_mut() {
+      self.written += chunk.len() as u64;
+      if self.written > MAX_EXTRACTED_BYTES {
+        return Err(self.too_large())
+      }
+
+      file.write_all(chunk).map_err(|source| InstallError::Io { source })?;
+    } else if let Some((_, target)) = self.link.as_mut() {
+      let remaining = MAX_LINK_TARGET.saturating_sub(target.len());
+      target.extend_from_slice(&chunk[..chunk.len().min(remaining)]);
+    }
+
+    Ok(())
+  }
+
+  fn end(&mut self) -> Result<(), InstallError> {
+    if let Some((link, target)) = self.link.take() {
+      let target = String::from_utf8_lossy(&target).to_string();
+      if link_escapes(&link, Path::new(&target)) {
+        return Err(InstallError::UnsafeArchive { detail: format!("link `{}` points outside of the mod folder, to `{}`", link.display(), target) })
+      }
+    }
+
+    self.current = None;
+    self.entries += 1;
+    if self.entries % REPORT_ENTRIES == 0 {
+      self.progress.report(InstallProgress::Extracting { entries: self.entries, total: self.total });
+    }
+
+    Ok(())
+  }
+}
+
+/**
+ * Reads any archive libarchive understands through compress_tools. It doesn't say where links point, so a link can't be
+ * checked and the whole archive is refused instead.
+ */
+fn read_libarchive(path: &Path, visitor: &mut dyn EntryVisitor) -> Result<(), InstallError> {
+  let source = File::open(path).map_err(|source| InstallError::Io { source })?;
+
+  for contents in ArchiveIterator::from_read(source).map_err(|source| InstallError::CompressTools { source })? {
+    match contents {
+      ArchiveContents::StartOfEntry(name, stat) => {
+        let file_type = stat.st_mode as u32 & FILE_TYPE_MASK;
+        let kind = if file_type == DIRECTORY || name.ends_with('/') {
+          EntryKind::Directory
+        } else if file_type == REGULAR_FILE {
+          EntryKind::File
+        } else if file_type == SYMLINK {
+          return Err(InstallError::UnsafeArchive { detail: format!("it holds a link, `{}`, which can't be checked", name) })
+        } else {
+          EntryKind::Other
+        };
+
+        visitor.start(EntryHeader { name, kind, size: stat.st_size.max(0) as u64 })?
+      },
+      ArchiveContents::DataChunk(chunk) => visitor.data(&chunk)?,
+      ArchiveContents::EndOfEntry => visitor.end()?,
+      ArchiveContents::Err(source) => return Err(InstallError::CompressTools { source })
+    }
+  }
+
+  Ok(())
+}
+
+fn count_libarchive(path: &Path) -> Option<u64> {
+  File::open(path).ok()
+    .and_then(|listing| list_archive_files(listing).ok())
+    .map(|files| files.len() as u64)
+}
+
+pub struct Zip;
+
+impl ArchiveFormat for Zip {
+  fn name(&self) -> &'static str {
+    "zip"
+  }
+
+  fn matches(&self, mime: Option<&str>, file_name: &str) -> bool {
+    matches_any(mime, file_name, &["application/zip"], &[".zip"])
+  }
+
+  fn read(&self, path: &Path, visitor: &mut dyn EntryVisitor) -> Result<(), InstallError> {
+    let source = File::open(path).map_err(|source| InstallError::Io { source })?;
+    let mut archive = zip::ZipArchive::new(source).map_err(|source| InstallError::Zip { source })?;
+
+    let mut buf = vec![0; 64 * 1024];
+    for idx in 0..archive.len() {
+      let mut file = archive.by_index(idx).map_err(|source| InstallError::Zip { source })?;
+      let kind = match file.unix_mode().map(|mode| mode & FILE_TYPE_MASK) {
+        _ if file.is_dir() => EntryKind::Directory,
+        // Archives made on Windows have no mode, and only ever hold files and folders
+        None | Some(REGULAR_FILE) => EntryKind::File,
+        Some(SYMLINK) => EntryKind::Symlink,
+        Some(_) => EntryKind::Other
+      };
+
+      visitor.start(EntryHeader { name: file.name().to_string(), kind, size: file.size() })?;
+      if kind == EntryKind::File || kind == EntryKind::Symlink {
+        loop {
+          let read = file.read(&mut buf).map_err(|source| InstallError::Io { source })?;
+          if read == 0 {
+            break;
+          }
+          visitor.data(&buf[..read])?;
+        }
+      }
+      visitor.end()?;
+    }
+
+    Ok(())
+  }
+
+  fn count(&self, path: &Path) -> Option<u64> {
+    File::open(path).ok()
+      .and_then(|source| zip::ZipArchive::new(source).ok())
+      .map(|archive| archive.len() as u64)
+  }
+}
+
+pub struct SevenZip;
+
+impl ArchiveFormat for SevenZip {
+  fn name(&self) -> &'static str {
+    "7z"
+  }
+
+  fn matches(&self, mime: Option<&str>, file_name: &str) -> bool {
+    matches_any(mime, file_name, &["application/x-7z-compressed"], &[".7z"])
+  }
+
+  fn read(&self, path: &Path, visitor: &mut dyn EntryVisitor) -> Result<(), InstallError> {
+    read_libarchive(path, visitor)
+  }
+
+  fn count(&self, path: &Path) -> Option<u64> {
+    count_libarchive(path)
+  }
+}
+
+pub struct Rar;
+
+impl ArchiveFormat for Rar {
+  fn name(&self) -> &'static str {
+    "rar"
+  }
+
+  fn matches(&self, mime: Option<&str>, file_name: &str) -> bool {
+    matches_any(mime, file_name, &["application/vnd.rar", "application/x-rar-compressed"], &[".rar"])
+  }
+
+  fn read(&self, path: &Path, visitor: &mut dyn EntryVisitor) -> Result<(), InstallError> {
+    read_libarchive(path, visitor)
+  }
+
+  fn count(&self, path: &Path) -> Option<u64> {
+    count_libarchive(path)
+  }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum TarCompression {
+  None,
+  Gzip,
+  Xz,
+  Bzip2,
+}
+
+/**
+ * A tarball, compressed or not.
+ */
+pub struct Tar(pub TarCompression);
+
+impl ArchiveFormat for Tar {
+  fn name(&self) -> &'static str {
+    match self.0 {
+      TarCompression::None => "tar",
+      TarCompression::Gzip => "tar.gz",
+      TarCompression::Xz => "tar.xz",
+      TarCompression::Bzip2 => "tar.bz2",
+    }
+  }
+
+  fn matches(&self, mime: Option<&str>, file_name: &str) -> bool {
+    match self.0 {
+      TarCompression::None => matches_any(mime, file_name, &["application/x-tar"], &[".tar"]),
+      TarCompression::Gzip => matches_any(mime, file_name, &["application/gzip"], &[".tar.gz", ".tgz"]),
+      TarCompression::Xz => matches_any(mime, file_name, &["application/x-xz"], &[".tar.xz", ".txz"]),
+      TarCompression::Bzip2 => matches_any(mime, file_name, &["application/x-bzip2"], &[".tar.bz2", ".tbz2", ".tbz"]),
+    }
+  }
+
+  fn read(&self, path: &Path, visitor: &mut dyn EntryVisitor) -> Result<(), InstallError> {
+    read_libarchive(path, visitor)
+  }
+
+  fn count(&self, path: &Path) -> Option<u64> {
+    // Listing a compressed tarball means decompressing all of it, which is as slow as extracting it
+    match self.0 {
+      TarCompression::None => count_libarchive(path),
+      _ => None
+    }
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+  use crate::installer::decompress;
+
+  /**
+   * A ustar entry, as a 512 byte header followed by its data padded to a whole block.
+   */
+  fn tar_entry(name: &str, type_flag: u8, link: &str, data: &[u8]) -> Vec<u8> {
+    let mut header = vec![0; 512];
+    header[..name.len()].copy_from_slice(name.as_bytes());
+    header[100..108].copy_from_slice(b"0000644\0");
+    header[108..116].copy_from_slice(b"0000000\0");
+    header[116..124].copy_from_slice(b"0000000\0");
+    header[124..136].copy_from_slice(format!("{:011o}\0", data.len()).as_bytes());
+    header[136..148].copy_from_slice(b"00000000000\0");
+    header[156] = type_flag;
+    header[157..157 + link.len()].copy_from_slice(link.as_bytes());
+    header[257..265].copy_from_slice(b"ustar\000");
+    header[148..156].copy_from_slice(b"        ");
+    let checksum: u32 = header.iter().map(|byte| *byte as u32).sum();
+    header[148..156].copy_from_slice(format!("{:06o}\0 ", checksum).as_bytes());
+
+    header.extend_from_slice(data);
+    header.resize(512 + (data.len() + 511) / 512 * 512, 0);
+    header
+  }
+
+  fn tar(dir: &Path, entries: &[Vec<u8>]) -> PathBuf {
+    let path = dir.join("mod.tar");
+    let mut bytes = entries.concat();
+    bytes.resize(bytes.len() + 1024, 0);
+    std::fs::write(&path, bytes).unwrap();
+
+    path
+  }
+
+  /**
+   * `links` are written as files holding their target, since zip 0.5 can't write links, then marked as links by patching
+   * their mode in the central directory.
+   */
+  fn zip(dir: &Path, files: &[(&str, &[u8])], links: &[(&str, &str)]) -> PathBuf {
+    let path = dir.join("mod.zip");
+    let mut writer = zip::ZipWriter::new(File::create(&path).unwrap());
+    for (name, data) in files {
+      writer.start_file(*name, zip::write::FileOptions::default()).unwrap();
+      writer.write_all(data).unwrap();
+    }
+    for (name, target) in links {
+      writer.start_file(*name, zip::write::FileOptions::default().unix_permissions(0o777)).unwrap();
+      writer.write_all(target.as_bytes()).unwrap();
+    }
+    writer.finish().unwrap();
+
+    let mut bytes = std::fs::read(&path).unwrap();
+    let (placeholder, link_mode) = (((REGULAR_FILE | 0o777) << 16).to_le_bytes(), ((SYMLINK | 0o777) << 16).to_le_bytes());
+    for idx in 0..bytes.len() - 4 {
+      if bytes[idx..idx + 4] == placeholder {
+        bytes[idx..idx + 4].copy_from_slice(&link_mode);
+      }
+    }
+    std::fs::write(&path, bytes).unwrap();
+
+    path
+  }
+
+  fn is_unsafe<T>(res: Result<T, InstallError>) -> bool {
+    matches!(res, Err(InstallError::UnsafeArchive { .. }))
+  }
+
+  #[test]
+  fn tar_refuses_entries_out_of_folder() {
+    let dir = tempfile::tempdir().unwrap();
+    let progress = ProgressReporter::default();
+
+    let parent = tar(dir.path(), &[tar_entry("mod/../../evil.txt", b'0', "", b"evil")]);
+    assert!(is_unsafe(decompress(parent, &progress)));
+    assert!(!dir.path().join("evil.txt").exists());
+
+    let absolute = tar(dir.path(), &[tar_entry("/evil.txt", b'0', "", b"evil")]);
+    assert!(is_unsafe(decompress(absolute, &progress)));
+  }
+
+  #[test]
+  fn tar_refuses_links() {
+    let dir = tempfile::tempdir().unwrap();
+    let progress = ProgressReporter::default();
+
+    let escaping = tar(dir.path(), &[
+      tar_entry("mod/a.txt", b'0', "", b"a"),
+      tar_entry("mod/link", b'2', "../../..", b""),
+    ]);
+    assert!(is_unsafe(decompress(escaping, &progress)));
+  }
+
+  #[test]
+  fn zip_refuses_entries_out_of_folder() {
+    let dir = tempfile::tempdir().unwrap();
+    let progress = ProgressReporter::default();
+
+    let parent = zip(dir.path(), &[("mod/../../evil.txt", b"evil")], &[]);
+    assert!(is_unsafe(decompress(parent, &progress)));
+    assert!(!dir.path().join("evil.txt").exists());
+
+    let absolute = zip(dir.path(), &[("/evil.txt", b"evil")], &[]);
+    assert!(is_unsafe(decompress(absolute, &progress)));
+  }
+
+  #[test]
+  fn zip_refuses_link_out_of_folder() {
+    let dir = tempfile::tempdir().unwrap();
+    let progress = ProgressReporter::default();
+
+    let escaping = zip(dir.path(), &[("mod/a.txt", b"a")], &[("mod/link", "../../..")]);
+    assert!(is_unsafe(decompress(escaping, &progress)));
+
+    let inside = zip(dir.path(), &[("mod/a.txt", b"a")], &[("mod/link", "a.txt")]);
+    let extracted = decompress(inside, &progress).unwrap();
+    assert_eq!(std::fs::read(extracted.path().join("mod").join("a.txt")).unwrap(), b"a");
+    assert!(std::fs::symlink_metadata(extracted.path().join("mod").join("link")).is_err());
+  }
+
+  fn visit(visitor: &mut dyn EntryVisitor, name: &str, kind: EntryKind, data: &[u8]) -> Result<(), InstallError> {
+    visitor.start(EntryHeader { name: name.to_string(), kind, size: data.len() as u64 })?;
+    visitor.data(data)?;
+    visitor.end()
+  }
+
+  #[test]
+  fn refuses_link_out_of_folder() {
+    let dest = tempfile::tempdir().unwrap();
+    let progress = ProgressReporter::default();
+    let mut extractor = Extractor::new(dest.path(), &progress, None);
+
+    let res = visit(&mut extractor, "a", EntryKind::Symlink, b"/tmp");
+
+    assert!(matches!(res, Err(InstallError::UnsafeArchive { .. })));
+  }
+
+  #[test]
+  fn never_writes_through_links() {
+    let dest = tempfile::tempdir().unwrap();
+    let progress = ProgressReporter::default();
+    let mut extractor = Extractor::new(dest.path(), &progress, None);
+
+    visit(&mut extractor, "b/", EntryKind::Directory, b"").unwrap();
+    visit(&mut extractor, "a", EntryKind::Symlink, b"b").unwrap();
+    visit(&mut extractor, "a/x", EntryKind::File, b"through the link").unwrap();
+
+    let a = std::fs::symlink_metadata(dest.path().join("a")).unwrap();
+    assert!(a.is_dir() && !a.file_type().is_symlink());
+    assert_eq!(std::fs::read(dest.path().join("a").join("x")).unwrap(), b"through the link");
+    assert!(!dest.path().join("b").join("x").exists());
+  }
+
+  #[test]
+  fn refuses_entries_out_of_folder() {
+    let dest = tempfile::tempdir().unwrap();
+    let progress = ProgressReporter::default();
+    let mut extractor = Extractor::new(dest.path(), &progress, None);
+
+    let res = visit(&mut extractor, "../evil.txt", EntryKind::File, b"evil");
+
+    assert!(matches!(res, Err(InstallError::UnsafeArchive { .. })));
+    assert!(!dest.path().parent().unwrap().join("evil.txt").exists());
+  }
+}
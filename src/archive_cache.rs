@@ -0,0 +1,215 @@
+use std::{path::{Path, PathBuf}, sync::Mutex};
+use serde::{Serialize, Deserialize};
+use lazy_static::lazy_static;
+
+use crate::config::config_dir;
+use crate::installer::InstallError;
+use crate::util::{now, with_json_file};
+
+const INDEX_FILE: &str = "index.json";
+
+lazy_static! {
+  /**
+   * Held while the index is read and written, so concurrent installs don't lose each other's entries.
+   */
+  static ref INDEX_LOCK: Mutex<()> = Mutex::new(());
+}
+
+/**
+ * A downloaded mod archive kept around so that installing the same version again, from any game install, doesn't need
+ * another download. The cache is capped in size, and the least recently used archives are evicted first.
+ */
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CachedArchive {
+  pub id: String,
+  pub version: String,
+  pub file_name: String,
+  pub url: String,
+  pub size: u64,
+  /**
+   * Seconds since the Unix epoch.
+   */
+  pub added_at: u64,
+  /**
+   * Seconds since the Unix epoch.
+   */
+  pub last_used: u64,
+  #[serde(skip)]
+  pub path: PathBuf,
+}
+
+pub async fn cache_dir(try_make: bool) -> PathBuf {
+  config_dir(try_make).await.join("archive_cache")
+}
+
+/**
+ * Runs `f` against the index, saving it afterwards if `f` returns true. Entries whose archive has gone missing are left out.
+ */
+async fn with_index<T, F>(try_make: bool, f: F) -> Result<T, InstallError>
+where
+  T: Send + 'static,
+  F: FnOnce(&Path, &mut Vec<CachedArchive>) -> Result<(T, bool), InstallError> + Send + 'static,
+{
+  let dir = cache_dir(try_make).await;
+  if try_make {
+    tokio::fs::create_dir_all(&dir).await.map_err(|source| InstallError::Io { source })?;
+  }
+
+  with_json_file(dir.join(INDEX_FILE), &INDEX_LOCK, move |entries: &mut Vec<CachedArchive>| {
+    entries.retain(|entry| dir.join(&entry.file_name).is_file());
+    for entry in entries.iter_mut() {
+      entry.path = dir.join(&entry.file_name);
+    }
+
+    match f(&dir, entries) {
+      Ok((res, changed)) => (Ok(res), changed),
+      Err(err) => (Err(err), false)
+    }
+  }).await.map_err(|source| InstallError::Io { source })?
+}
+
+/**
+ * Deletes least recently used entries until the cache fits in `limit` bytes. Returns the number of entries deleted.
+ */
+fn evict(entries: &mut Vec<CachedArchive>, limit: u64) -> usize {
+  entries.sort_by(|left, right| right.last_used.cmp(&left.last_used));
+
+  let mut total = 0;
+  let mut evicted = 0;
+  entries.retain(|entry| {
+    total += entry.size;
+    if total <= limit {
+      true
+    } else {
+      let _ = std::fs::remove_file(&entry.path);
+      evicted += 1;
+
+      false
+    }
+  });
+
+  evicted
+}
+
+/**
+ * Replaces anything that might not be valid in a file name.
+ */
+fn sanitise(text: &str) -> String {
+  text.chars()
+    .map(|c| if c.is_ascii_alphanumeric() || c == '.' || c == '-' || c == '_' { c } else { '_' })
+    .collect()
+}
+
+/**
+ * Every cached archive, most recently used first.
+ */
+pub async fn list() -> Vec<CachedArchive> {
+  with_index(false, |_, entries| {
+    entries.sort_by(|left, right| right.last_used.cmp(&left.last_used));
+
+    Ok((entries.clone(), false))
+  }).await.unwrap_or_default()
+}
+
+/**
+ * The cached archive of the given version of a mod, if there is one. Marks it as used.
+ */
+pub async fn get(id: &str, version: &str) -> Option<CachedArchive> {
+  let (id, version) = (id.to_string(), version.to_string());
+
+  with_index(false, move |_, entries| {
+    match entries.iter_mut().find(|entry| entry.id == id && entry.version == version) {
+      Some(entry) => {
+        entry.last_used = now();
+
+        Ok((Some(entry.clone()), true))
+      },
+      None => Ok((None, false))
+    }
+  }).await.ok().flatten()
+}
+
+/**
+ * Copies the archive at `file` into the cache as the given version of a mod, replacing any archive already cached for it,
+ * then evicts older archives until the cache fits in `limit` bytes. Nothing is cached if `limit` is 0 or the archive alone
+ * is larger than it.
+ */
+pub async fn insert(id: &str, version: &str, url: &str, file: &Path, limit: u64) -> Result<Option<CachedArchive>, InstallError> {
+  let size = tokio::fs::metadata(file).await.map_err(|source| InstallError::Io { source })?.len();
+  if limit == 0 || size > limit {
+    return Ok(None)
+  }
+
+  let (id, version, url, file) = (id.to_string(), version.to_string(), url.to_string(), file.to_path_buf());
+  with_index(true, move |dir, entries| {
+    if let Some(idx) = entries.iter().position(|entry| entry.id == id && entry.version == version) {
+      let _ = std::fs::remove_file(&entries.remove(idx).path);
+    }
+
+    let base = format!("{}-{}", sanitise(&id), sanitise(&version));
+    let mut file_name = base.clone();
+    let mut suffix = 1;
+    while dir.join(&file_name).exists() {
+      file_name = format!("{}-{}", base, suffix);
+      suffix += 1;
+    }
+
+    let path = dir.join(&file_name);
+    // The download folder lives next to the cache, so a hard link usually saves copying the archive
+    if std::fs::hard_link(&file, &path).is_err() {
+      std::fs::copy(&file, &path).map_err(|source| InstallError::Io { source })?;
+    }
+
+    let entry = CachedArchive {
+      id,
+      version,
+      file_name,
+      url,
+      size,
+      added_at: now(),
+      last_used: now(),
+      path,
+    };
+    // At the front so it's the last to go among archives used in the same second
+    entries.insert(0, entry.clone());
+    evict(entries, limit);
+
+    Ok((Some(entry), true))
+  }).await
+}
+
+/**
+ * Deletes the cached archive of the given version of a mod, if there is one.
+ */
+pub async fn remove(id: &str, version: &str) -> Result<(), InstallError> {
+  let (id, version) = (id.to_string(), version.to_string());
+
+  with_index(false, move |_, entries| {
+    match entries.iter().position(|entry| entry.id == id && entry.version == version) {
+      Some(idx) => {
+        std::fs::remove_file(&entries.remove(idx).path).map_err(|source| InstallError::Io { source })?;
+
+        Ok(((), true))
+      },
+      None => Ok(((), false))
+    }
+  }).await
+}
+
+/**
+ * Deletes least recently used archives until the cache fits in `limit` bytes. Returns the number of archives deleted.
+ */
+pub async fn prune(limit: u64) -> usize {
+  with_index(false, move |_, entries| {
+    let evicted = evict(entries, limit);
+
+    Ok((evicted, evicted > 0))
+  }).await.unwrap_or_default()
+}
+
+/**
+ * Deletes every cached archive.
+ */
+pub async fn clear() -> usize {
+  prune(0).await
+}
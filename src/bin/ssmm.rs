@@ -0,0 +1,749 @@
+use std::{env, path::PathBuf, process};
+use serde_json::{json, Value};
+
+use starsector_mod_manager::{GameInstall, ModRepository};
+use starsector_mod_manager::config::Config;
+use starsector_mod_manager::installer::{DownloadOptions, InstallError};
+use starsector_mod_manager::mods::{ModEntry, UpdateStatus, UpdateHold, DependencyStatus};
+use starsector_mod_manager::repository::InstallOutcome;
+use starsector_mod_manager::profiles::Profiles;
+use starsector_mod_manager::trash::TrashEntry;
+use starsector_mod_manager::util::format_timestamp;
+
+const EXIT_OK: i32 = 0;
+const EXIT_FAILURE: i32 = 1;
+const EXIT_USAGE: i32 = 2;
+
+const USAGE: &str = r#"Usage: ssmm [--install-dir <path>] [--json] <command> [args]
+
+Commands:
+  list                              List installed mods
+  enable <id>...                    Enable the given mods
+  disable <id>...                   Disable the given mods
+  install [--replace] <source>...   Install mods from archives, folders or URLs
+  preview <source>...               Show what the given archives or folders would install, without installing
+  uninstall [--move-to <dir>] <id>...
+                                    Move the given mods to the trash, or out of the mods folder
+  check-updates                     Check every mod with a Version Checker file for updates
+  update (--all | <id>...)          Download and install available updates
+  hold <id> [<version>]             Stop offering updates for a mod, or only the given version of it
+  unhold <id>                       Offer updates for a mod again
+  profiles                          List saved mod profiles
+  switch-profile <name>             Enable exactly the mods in the given profile
+  conflicts                         List files and spreadsheet rows shared by enabled mods
+  trash                             List recently uninstalled and replaced mods
+  restore [--replace] <id>...       Restore the most recently removed copy of the given mods
+
+Options:
+  --install-dir <path>   Starsector install to operate on. Defaults to the one set in the mod manager
+  --json                 Print machine readable output
+  --replace              Replace mods that are already installed, moving the old copy to the trash
+  --move-to <dir>        Move uninstalled mods into the given folder instead of the trash
+  -h, --help             Print this help
+
+Exit codes:
+  0   Success
+  1   One or more operations failed
+  2   Invalid usage or no install directory available, for commands that need one"#;
+
+struct Args {
+  install_dir: Option<PathBuf>,
+  json: bool,
+  replace: bool,
+  all: bool,
+  move_to: Option<PathBuf>,
+  command: String,
+  targets: Vec<String>,
+}
+
+fn parse_args<I: Iterator<Item = String>>(mut raw: I) -> Result<Args, String> {
+  let mut args = Args {
+    install_dir: None,
+    json: false,
+    replace: false,
+    all: false,
+    move_to: None,
+    command: String::new(),
+    targets: vec![],
+  };
+
+  while let Some(arg) = raw.next() {
+    match arg.as_str() {
+      "--install-dir" => {
+        args.install_dir = Some(raw.next().map(PathBuf::from).ok_or_else(|| format!("--install-dir requires a path"))?);
+      },
+      "--move-to" => {
+        args.move_to = Some(raw.next().map(PathBuf::from).ok_or_else(|| format!("--move-to requires a path"))?);
+      },
+      "--json" => args.json = true,
+      "--replace" => args.replace = true,
+      "--all" => args.all = true,
+      "-h" | "--help" => {
+        args.command = String::from("help");
+
+        return Ok(args)
+      },
+      flag if flag.starts_with("--") => return Err(format!("Unknown option `{}`", flag)),
+      _ if args.command.is_empty() => args.command = arg,
+      _ => args.targets.push(arg)
+    }
+  }
+
+  match args.command.as_str() {
+    "" => Err(format!("No command given")),
+    "list" | "check-updates" | "profiles" | "conflicts" | "trash" => Ok(args),
+    "enable" | "disable" | "install" | "preview" | "uninstall" | "restore" if args.targets.is_empty() => Err(format!("`{}` requires at least one argument", args.command)),
+    "enable" | "disable" | "install" | "preview" | "uninstall" | "restore" => Ok(args),
+    "update" if args.all == args.targets.is_empty() => Err(format!("`update` requires either --all or at least one mod ID")),
+    "update" => Ok(args),
+    "hold" if args.targets.is_empty() || args.targets.len() > 2 => Err(format!("`hold` requires a mod ID, optionally followed by a version")),
+    "hold" => Ok(args),
+    "unhold" if args.targets.len() != 1 => Err(format!("`unhold` requires exactly one mod ID")),
+    "unhold" => Ok(args),
+    "switch-profile" if args.targets.len() != 1 => Err(format!("`switch-profile` requires exactly one profile name")),
+    "switch-profile" => Ok(args),
+    other => Err(format!("Unknown command `{}`", other))
+  }
+}
+
+fn main() {
+  let args = match parse_args(env::args().skip(1)) {
+    Ok(args) => args,
+    Err(err) => {
+      eprintln!("{}\n", err);
+      eprintln!("{}", USAGE);
+      process::exit(EXIT_USAGE);
+    }
+  };
+  if args.command == "help" {
+    println!("{}", USAGE);
+    process::exit(EXIT_OK);
+  }
+
+  let runtime = tokio::runtime::Builder::new_current_thread()
+    .enable_all()
+    .build()
+    .expect("Build async runtime");
+
+  process::exit(runtime.block_on(run(args)));
+}
+
+async fn run(args: Args) -> i32 {
+  // The trash is kept in the manager's own folder, so it can be listed without knowing which install it came from
+  if args.command == "trash" {
+    return trash(args.json).await;
+  }
+
+  let config = Config::load().await.ok();
+  let install_dir = args.install_dir.clone().or_else(|| config.as_ref().and_then(|config| config.install_dir.clone()));
+  let repository = match install_dir {
+    Some(install_dir) => match GameInstall::new(install_dir).scan_mods() {
+      Ok(repository) => Some(repository),
+      Err(err) => {
+        eprintln!("Failed to read mods folder: {:?}", err);
+        return EXIT_FAILURE;
+      }
+    },
+    None => None
+  };
+  if args.command == "profiles" {
+    return profiles(repository.as_ref(), args.json).await;
+  }
+
+  let mut repository = match repository {
+    Some(repository) => repository,
+    None => {
+      eprintln!("No Starsector install directory given, and none is set in the mod manager. Use --install-dir <path>.");
+      return EXIT_USAGE;
+    }
+  };
+  if let Some(config) = &config {
+    repository.download_options = DownloadOptions::from_config(config);
+    repository.apply_update_holds(&config.update_holds);
+  }
+
+  match args.command.as_str() {
+    "list" => list(&repository, args.json),
+    "enable" => set_enabled(&mut repository, &args.targets, true, args.json).await,
+    "disable" => set_enabled(&mut repository, &args.targets, false, args.json).await,
+    "install" => install(&repository, &args.targets, args.replace, args.json).await,
+    "preview" => preview(&repository, &args.targets, args.json).await,
+    "uninstall" => uninstall(&mut repository, &args.targets, args.move_to, args.json).await,
+    "check-updates" => check_updates(&mut repository, args.json).await,
+    "update" => update(&mut repository, &args.targets, args.all, args.json).await,
+    "hold" => set_hold(&repository, config, &args.targets[0], Some(args.targets.get(1).map_or(UpdateHold::All, |version| UpdateHold::Version(version.clone()))), args.json).await,
+    "unhold" => set_hold(&repository, config, &args.targets[0], None, args.json).await,
+    "conflicts" => conflicts(&repository, args.json),
+    "switch-profile" => switch_profile(&mut repository, &args.targets[0], args.json).await,
+    "restore" => restore(&args.targets, args.replace, args.json).await,
+    _ => unreachable!()
+  }
+}
+
+fn sorted(repository: &ModRepository) -> Vec<&ModEntry> {
+  let mut mods: Vec<&ModEntry> = repository.mods.values().collect();
+  mods.sort_by(|left, right| left.id.cmp(&right.id));
+
+  mods
+}
+
+fn entry_json(entry: &ModEntry) -> Value {
+  json!({
+    "id": entry.id,
+    "name": entry.name,
+    "author": entry.author,
+    "version": entry.version.to_string(),
+    "game_version": entry.game_version,
+    "total_conversion": entry.total_conversion,
+    "utility": entry.utility,
+    "jars": entry.jars,
+    "mod_plugin": entry.mod_plugin,
+    "dependencies": entry.dependencies.iter().map(|dependency| &dependency.id).collect::<Vec<&String>>(),
+    "enabled": entry.enabled,
+    "path": entry.path,
+    "update_status": entry.update_status.as_ref().map(|status| status.to_string()),
+    "update_hold": entry.update_hold.as_ref().map(|hold| hold.to_string()),
+    "update_cached_at": entry.remote_cached_at,
+    "bundled_versions": entry.bundled_versions.iter().map(|bundled| json!({
+      "name": bundled.name,
+      "version": bundled.local.version.to_string(),
+      "update_status": bundled.update_status.as_ref().map(|status| status.to_string()),
+      "remote_version": bundled.remote.as_ref().map(|remote| remote.version.to_string()),
+      "update_cached_at": bundled.remote_cached_at,
+    })).collect::<Vec<Value>>(),
+    "remote_version": entry.remote_version.as_ref().map(|remote| remote.version.to_string()),
+    "direct_download_url": entry.remote_version.as_ref().and_then(|remote| remote.direct_download_url.clone()),
+    "changelog_url": entry.changelog_url(),
+  })
+}
+
+fn list(repository: &ModRepository, as_json: bool) -> i32 {
+  let mods = sorted(repository);
+
+  if as_json {
+    println!("{}", Value::Array(mods.into_iter().map(entry_json).collect()));
+  } else {
+    for entry in mods {
+      let kind = if entry.total_conversion { " [total conversion]" } else if entry.utility { " [utility]" } else { "" };
+      println!("[{}] {} ({}) v{} - Starsector {}{}", if entry.enabled { "x" } else { " " }, entry.name, entry.id, entry.version, entry.game_version, kind);
+    }
+  }
+
+  EXIT_OK
+}
+
+async fn set_enabled(repository: &mut ModRepository, ids: &[String], enabled: bool, as_json: bool) -> i32 {
+  let (found, missing): (Vec<&String>, Vec<&String>) = ids.iter().partition(|id| repository.set_enabled(id, enabled));
+
+  // A total conversion alongside other content mods won't load at all, so that's refused rather than warned about
+  let excluded: Vec<(String, String)> = repository.enabled_exclusions()
+    .into_iter()
+    .filter(|(id, other)| enabled && (found.contains(&id) || found.contains(&other)))
+    .collect();
+  if !excluded.is_empty() {
+    if as_json {
+      println!("{}", json!({
+        "enabled": enabled,
+        "changed": [],
+        "not_found": missing,
+        "excluded": excluded,
+        "saved": false,
+      }));
+    } else {
+      for (id, other) in &excluded {
+        eprintln!("{} and {} can't be enabled together, as one of them is a total conversion", id, other);
+      }
+      eprintln!("Nothing was changed");
+    }
+
+    return EXIT_FAILURE;
+  }
+
+  let saved = repository.save_enabled().await;
+
+  // Other problems are only reported, never acted on - scripts can resolve them explicitly
+  let warnings: Vec<String> = found.iter()
+    .flat_map(|id| if enabled {
+      repository.dependency_statuses(id)
+        .into_iter()
+        .filter(|(_, status)| *status != DependencyStatus::Satisfied)
+        .map(|(dependency, status)| format!("{} depends on {} ({})", id, dependency, status))
+        .collect::<Vec<String>>()
+    } else {
+      repository.dependents(id)
+        .into_iter()
+        .map(|dependent| format!("{} is still enabled and depends on {}", dependent, id))
+        .collect()
+    })
+    .collect();
+
+  if as_json {
+    println!("{}", json!({
+      "enabled": enabled,
+      "changed": found,
+      "not_found": missing,
+      "warnings": warnings,
+      "saved": saved.is_ok(),
+    }));
+  } else {
+    for warning in &warnings {
+      eprintln!("Warning: {}", warning);
+    }
+    for id in &found {
+      println!("{} {}", if enabled { "Enabled" } else { "Disabled" }, id);
+    }
+    for id in &missing {
+      eprintln!("No mod with ID `{}` is installed", id);
+    }
+    if let Err(err) = &saved {
+      eprintln!("Failed to save enabled_mods.json: {:?}", err);
+    }
+  }
+
+  if missing.is_empty() && saved.is_ok() { EXIT_OK } else { EXIT_FAILURE }
+}
+
+async fn install(repository: &ModRepository, sources: &[String], replace: bool, as_json: bool) -> i32 {
+  let mut code = EXIT_OK;
+  let mut results = vec![];
+
+  for source in sources {
+    let res = if source.starts_with("http://") || source.starts_with("https://") {
+      repository.install_from_url(source.clone(), replace).await
+    } else {
+      repository.install(PathBuf::from(source), replace).await
+    };
+
+    // An archive can hold several mods, each of which is reported on separately
+    let outcomes = match res {
+      Ok(outcomes) => outcomes,
+      Err(err) => vec![Err(err)]
+    };
+
+    for outcome in outcomes {
+      let (status, detail) = match outcome {
+        Ok(InstallOutcome::Installed(id)) => ("installed", id),
+        Ok(InstallOutcome::Replaced(id)) => ("replaced", id),
+        Ok(InstallOutcome::Duplicate(id, path)) => {
+          code = EXIT_FAILURE;
+          ("duplicate", format!("{} already exists at {}. Use --replace to overwrite it", id, path.display()))
+        },
+        Err(err) => {
+          code = EXIT_FAILURE;
+          ("failed", err.to_string())
+        }
+      };
+
+      if as_json {
+        results.push(json!({ "source": source, "status": status, "detail": detail }));
+      } else if status == "installed" || status == "replaced" {
+        println!("{}: {} {}", source, status, detail);
+      } else {
+        eprintln!("{}: {} - {}", source, status, detail);
+      }
+    }
+  }
+
+  if as_json {
+    println!("{}", Value::Array(results));
+  }
+
+  code
+}
+
+async fn preview(repository: &ModRepository, sources: &[String], as_json: bool) -> i32 {
+  let mut code = EXIT_OK;
+  let mut results = vec![];
+
+  for source in sources {
+    let preview = match repository.preview(PathBuf::from(source)).await {
+      Ok(preview) => preview,
+      Err(err) => {
+        code = EXIT_FAILURE;
+        if as_json {
+          results.push(json!({ "source": source, "error": err.to_string() }));
+        } else {
+          eprintln!("{}: failed - {}", source, err);
+        }
+
+        continue;
+      }
+    };
+    if preview.mods.is_empty() {
+      code = EXIT_FAILURE;
+    }
+
+    if as_json {
+      results.push(json!({
+        "source": source,
+        "size": preview.size,
+        "mods": preview.mods.iter().map(|previewed| json!({
+          "folder": previewed.folder,
+          "id": previewed.mod_info.id,
+          "name": previewed.mod_info.name,
+          "version": previewed.mod_info.version.to_string(),
+          "game_version": previewed.mod_info.game_version,
+          "replaces": previewed.replaces,
+        })).collect::<Vec<Value>>(),
+      }));
+    } else {
+      println!("{}: {} mod(s), {:.1} MB uncompressed", source, preview.mods.len(), preview.size as f64 / (1024.0 * 1024.0));
+      for previewed in &preview.mods {
+        let replaces = previewed.replaces.as_ref().map_or_else(String::new, |path| format!(", replaces {}", path.display()));
+        println!("  {} ({}) v{} - Starsector {}{}", previewed.mod_info.name, previewed.mod_info.id, previewed.mod_info.version, previewed.mod_info.game_version, replaces);
+      }
+      if preview.mods.is_empty() {
+        eprintln!("{}: no mod found", source);
+      }
+    }
+  }
+
+  if as_json {
+    println!("{}", Value::Array(results));
+  }
+
+  code
+}
+
+async fn uninstall(repository: &mut ModRepository, ids: &[String], move_to: Option<PathBuf>, as_json: bool) -> i32 {
+  let mut code = EXIT_OK;
+  let mut results = vec![];
+
+  for id in ids {
+    let dependents: Vec<String> = repository.dependents(id)
+      .into_iter()
+      .filter(|dependent| !ids.contains(dependent))
+      .collect();
+
+    let (status, detail) = match repository.uninstall(id, move_to.clone()).await {
+      Ok(()) if dependents.is_empty() => ("removed", String::new()),
+      Ok(()) => ("removed", format!("Still enabled and depending on it: {}", dependents.join(", "))),
+      Err(err) => {
+        code = EXIT_FAILURE;
+        ("failed", err.to_string())
+      }
+    };
+
+    if as_json {
+      results.push(json!({ "id": id, "status": status, "detail": detail }));
+    } else if status == "failed" {
+      eprintln!("{}: {} - {}", id, status, detail);
+    } else if detail.is_empty() {
+      println!("{}: {}", id, status);
+    } else {
+      println!("{}: {} - Warning: {}", id, status, detail);
+    }
+  }
+
+  if as_json {
+    println!("{}", Value::Array(results));
+  }
+
+  code
+}
+
+async fn check_updates(repository: &mut ModRepository, as_json: bool) -> i32 {
+  repository.check_updates().await;
+
+  let mods: Vec<&ModEntry> = sorted(repository)
+    .into_iter()
+    .filter(|entry| entry.version_checker.is_some())
+    .collect();
+
+  if as_json {
+    println!("{}", Value::Array(mods.into_iter().map(entry_json).collect()));
+  } else {
+    for entry in mods {
+      let cached = entry.remote_cached_at
+        .map(|fetched_at| format!(" [check failed, as of {}]", format_timestamp(fetched_at)))
+        .unwrap_or_default();
+
+      match &entry.update_status {
+        Some(UpdateStatus::Major(remote)) | Some(UpdateStatus::Minor(remote)) | Some(UpdateStatus::Patch(remote)) => {
+          println!("{} ({}): {} -> {} ({} update{}){}", entry.name, entry.id, entry.version, remote, entry.update_status.as_ref().unwrap(),
+            if entry.remote_version.as_ref().and_then(|r| r.direct_download_url.as_ref()).is_some() { "" } else { ", manual download required" },
+            cached
+          );
+        },
+        Some(status) => println!("{} ({}): {}{}", entry.name, entry.id, status, cached),
+        None => println!("{} ({}): Unknown", entry.name, entry.id)
+      }
+      for bundled in &entry.bundled_versions {
+        let cached = bundled.remote_cached_at
+          .map(|fetched_at| format!(" [check failed, as of {}]", format_timestamp(fetched_at)))
+          .unwrap_or_default();
+
+        match (&bundled.update_status, &bundled.remote) {
+          (Some(status), Some(remote)) if status.is_update_available() => {
+            println!("  bundled {}: {} -> {} ({} update, manual download required){}", bundled.name, bundled.local.version, remote.version, status, cached);
+          },
+          (Some(status), _) => println!("  bundled {}: {}{}", bundled.name, status, cached),
+          (None, _) => println!("  bundled {}: Unknown", bundled.name)
+        }
+      }
+    }
+  }
+
+  EXIT_OK
+}
+
+async fn update(repository: &mut ModRepository, ids: &[String], all: bool, as_json: bool) -> i32 {
+  repository.check_updates().await;
+
+  let targets: Vec<String> = if all {
+    sorted(repository)
+      .into_iter()
+      .filter(|entry| entry.update_status.as_ref().map_or(false, UpdateStatus::is_update_available))
+      .map(|entry| entry.id.clone())
+      .collect()
+  } else {
+    ids.to_vec()
+  };
+
+  let mut code = EXIT_OK;
+  let mut results = vec![];
+  for id in targets {
+    let (status, detail) = match repository.update(&id).await {
+      Ok(name) => ("updated", name),
+      Err(InstallError::UpdateHeld { .. }) => ("held", format!("Updates are held, use `unhold {}` to allow them", id)),
+      Err(InstallError::NoUpdate { .. }) => ("skipped", format!("No update available")),
+      Err(InstallError::ManualUpdate { .. }) => ("manual", format!("Update available, but it must be downloaded manually")),
+      Err(err) => ("failed", err.to_string())
+    };
+
+    if status == "failed" {
+      code = EXIT_FAILURE;
+    }
+
+    if as_json {
+      results.push(json!({ "id": id, "status": status, "detail": detail }));
+    } else if status == "failed" {
+      eprintln!("{}: {} - {}", id, status, detail);
+    } else {
+      println!("{}: {} - {}", id, status, detail);
+    }
+  }
+
+  if as_json {
+    println!("{}", Value::Array(results));
+  }
+
+  code
+}
+
+fn conflicts(repository: &ModRepository, as_json: bool) -> i32 {
+  let report = repository.file_conflicts();
+
+  if as_json {
+    println!("{}", json!({
+      "files": report.pairs.iter().map(|((left, right), conflicts)| json!({
+        "mods": [left, right],
+        "files": conflicts.iter().map(|conflict| json!({ "path": conflict.path, "kind": conflict.kind.to_string() })).collect::<Vec<Value>>(),
+      })).collect::<Vec<Value>>(),
+      "rows": report.rows.iter().map(|row| json!({
+        "file": row.file,
+        "id": row.id,
+        "mods": row.mods,
+        "winner": row.winner(),
+      })).collect::<Vec<Value>>(),
+    }));
+  } else if report.is_empty() {
+    println!("No enabled mods share any files");
+  } else {
+    for ((left, right), conflicts) in &report.pairs {
+      println!("{} <-> {}", left, right);
+      for conflict in conflicts {
+        println!("  [{}] {}", conflict.kind, conflict.path);
+      }
+    }
+    for row in &report.rows {
+      println!("{} `{}` is defined by {} - {} wins", row.file, row.id, row.mods.join(", "), row.winner());
+    }
+  }
+
+  EXIT_OK
+}
+
+async fn set_hold(repository: &ModRepository, config: Option<Config>, id: &str, hold: Option<UpdateHold>, as_json: bool) -> i32 {
+  let mut config = match config {
+    Some(config) => config,
+    None => {
+      eprintln!("Holds are saved in the mod manager's settings, which haven't been created yet. Open the mod manager once first.");
+      return EXIT_FAILURE;
+    }
+  };
+  if repository.get(id).is_none() {
+    eprintln!("No mod with ID `{}` is installed", id);
+    return EXIT_FAILURE;
+  }
+
+  match &hold {
+    Some(hold) => config.update_holds.insert(id.to_string(), hold.clone()),
+    None => config.update_holds.remove(id)
+  };
+  let saved = config.save().await;
+
+  if as_json {
+    println!("{}", json!({
+      "id": id,
+      "hold": hold.as_ref().map(|hold| hold.to_string()),
+      "saved": saved.is_ok(),
+    }));
+  } else if let Err(err) = &saved {
+    eprintln!("Failed to save settings: {:?}", err);
+  } else {
+    match &hold {
+      Some(hold) => println!("{}: {}", id, hold),
+      None => println!("{}: updates allowed", id)
+    }
+  }
+
+  if saved.is_ok() { EXIT_OK } else { EXIT_FAILURE }
+}
+
+async fn profiles(repository: Option<&ModRepository>, as_json: bool) -> i32 {
+  let profiles = match Profiles::load().await {
+    Ok(profiles) => profiles,
+    Err(err) => {
+      eprintln!("Failed to read profiles: {:?}", err);
+      return EXIT_FAILURE;
+    }
+  };
+  // Whether the active profile has been modified can only be told with an install to compare it against
+  let modified = repository.map(|repository| profiles.is_modified(&repository.enabled_mods().enabled_mods));
+
+  if as_json {
+    println!("{}", json!({
+      "active": profiles.active,
+      "modified": modified,
+      "profiles": profiles.profiles,
+    }));
+  } else {
+    for (name, mods) in &profiles.profiles {
+      let active = profiles.active.as_ref() == Some(name);
+      println!("[{}] {} ({} mods){}", if active { "x" } else { " " }, name, mods.len(), if active && modified == Some(true) { " - modified" } else { "" });
+    }
+  }
+
+  EXIT_OK
+}
+
+async fn switch_profile(repository: &mut ModRepository, name: &str, as_json: bool) -> i32 {
+  let mut profiles = match Profiles::load().await {
+    Ok(profiles) => profiles,
+    Err(err) => {
+      eprintln!("Failed to read profiles: {:?}", err);
+      return EXIT_FAILURE;
+    }
+  };
+  let profile = match profiles.get(name) {
+    Some(profile) => profile.clone(),
+    None => {
+      eprintln!("No profile named `{}` exists", name);
+      return EXIT_FAILURE;
+    }
+  };
+
+  let missing = repository.apply_profile(&profile);
+  let excluded = repository.enabled_exclusions();
+  if !excluded.is_empty() {
+    if as_json {
+      println!("{}", json!({
+        "profile": name,
+        "not_found": missing,
+        "excluded": excluded,
+        "saved": false,
+      }));
+    } else {
+      for (id, other) in &excluded {
+        eprintln!("{} and {} can't be enabled together, as one of them is a total conversion", id, other);
+      }
+      eprintln!("Did not switch to profile {}", name);
+    }
+
+    return EXIT_FAILURE;
+  }
+  let saved = repository.save_enabled().await;
+  if saved.is_ok() {
+    profiles.active = Some(name.to_string());
+  }
+  let profiles_saved = profiles.save().await;
+
+  if as_json {
+    println!("{}", json!({
+      "profile": name,
+      "not_found": missing,
+      "saved": saved.is_ok() && profiles_saved.is_ok(),
+    }));
+  } else {
+    for id in &missing {
+      eprintln!("No mod with ID `{}` is installed, skipping", id);
+    }
+    match (&saved, &profiles_saved) {
+      (Err(err), _) => eprintln!("Failed to save enabled_mods.json: {:?}", err),
+      (_, Err(err)) => eprintln!("Failed to save profiles: {:?}", err),
+      _ => println!("Switched to profile {}", name)
+    }
+  }
+
+  if saved.is_ok() && profiles_saved.is_ok() { EXIT_OK } else { EXIT_FAILURE }
+}
+
+async fn trash(as_json: bool) -> i32 {
+  let entries = TrashEntry::list().await;
+
+  if as_json {
+    println!("{}", Value::Array(entries.iter().map(|entry| json!({
+      "id": entry.id,
+      "name": entry.name,
+      "version": entry.version,
+      "original_path": entry.original_path,
+      "removed_at": entry.removed_at,
+      "reason": entry.reason.to_string(),
+    })).collect()));
+  } else if entries.is_empty() {
+    println!("No mods have been removed recently");
+  } else {
+    for entry in &entries {
+      println!("{} {} ({}) - {} {}", entry.id, entry.version, entry.name, entry.reason, format_timestamp(entry.removed_at));
+    }
+  }
+
+  EXIT_OK
+}
+
+async fn restore(ids: &[String], replace: bool, as_json: bool) -> i32 {
+  let mut code = EXIT_OK;
+  let mut results = vec![];
+  let entries = TrashEntry::list().await;
+
+  for id in ids {
+    // Entries are listed newest first
+    let res = match entries.iter().find(|entry| entry.id == *id) {
+      Some(entry) => entry.clone().restore(replace).await.map_err(|err| err.to_string()),
+      None => Err(format!("No removed copy of `{}` found", id))
+    };
+
+    let (status, detail) = match res {
+      Ok(path) => ("restored", path.display().to_string()),
+      Err(err) => {
+        code = EXIT_FAILURE;
+        ("failed", err)
+      }
+    };
+
+    if as_json {
+      results.push(json!({ "id": id, "status": status, "detail": detail }));
+    } else if status == "failed" {
+      eprintln!("{}: {} - {}", id, status, detail);
+    } else {
+      println!("{}: {} to {}", id, status, detail);
+    }
+  }
+
+  if as_json {
+    println!("{}", Value::Array(results));
+  }
+
+  code
+}
@@ -0,0 +1,135 @@
+use std::{path::PathBuf, collections::HashMap};
+use serde::{Serialize, Deserialize};
+
+use crate::{LoadError, SaveError};
+use crate::mods::UpdateHold;
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Config {
+  pub install_dir: Option<PathBuf>,
+  pub last_browsed: Option<PathBuf>,
+  pub git_warn: bool,
+  pub experimental_launch: bool,
+  pub experimental_resolution: (u32, u32),
+  /**
+   * How many days uninstalled and replaced mods are kept in the trash before being deleted for good. 0 keeps them forever.
+   */
+  #[serde(default = "default_trash_retention")]
+  pub trash_retention_days: u32,
+  /**
+   * How many archives or folders can be extracted and installed at the same time. Anything beyond that waits in the queue.
+   */
+  #[serde(default = "default_max_concurrent_installs")]
+  pub max_concurrent_installs: u32,
+  /**
+   * How many times a failed download is retried before giving up.
+   */
+  #[serde(default = "default_download_retries")]
+  pub download_retries: u32,
+  /**
+   * How many seconds a download can go without a response before the attempt is abandoned and retried.
+   */
+  #[serde(default = "default_download_timeout")]
+  pub download_timeout_secs: u64,
+  /**
+   * How many megabytes of downloaded archives to keep for reinstalls. 0 disables the archive cache.
+   */
+  #[serde(default = "default_archive_cache_mb")]
+  pub archive_cache_mb: u64,
+  /**
+   * Mods whose updates shouldn't be offered, by ID.
+   */
+  #[serde(default)]
+  pub update_holds: HashMap<String, UpdateHold>,
+}
+
+impl Default for Config {
+  fn default() -> Self {
+    Config {
+      install_dir: None,
+      last_browsed: None,
+      git_warn: false,
+      experimental_launch: false,
+      experimental_resolution: (1280, 768),
+      trash_retention_days: default_trash_retention(),
+      max_concurrent_installs: default_max_concurrent_installs(),
+      download_retries: default_download_retries(),
+      download_timeout_secs: default_download_timeout(),
+      archive_cache_mb: default_archive_cache_mb(),
+      update_holds: HashMap::new(),
+    }
+  }
+}
+
+fn default_trash_retention() -> u32 {
+  14
+}
+
+fn default_max_concurrent_installs() -> u32 {
+  2
+}
+
+fn default_download_retries() -> u32 {
+  5
+}
+
+fn default_download_timeout() -> u64 {
+  30
+}
+
+fn default_archive_cache_mb() -> u64 {
+  2048
+}
+
+/**
+ * The directory the manager keeps its own files in, falling back to the working directory if it can't be found or created.
+ */
+pub async fn config_dir(try_make: bool) -> PathBuf {
+  use directories::ProjectDirs;
+  use tokio::fs;
+
+  if let Some(proj_dirs) = ProjectDirs::from("org", "laird", "Starsector Mod Manager") {
+    if proj_dirs.config_dir().exists() || (try_make && fs::create_dir_all(proj_dirs.config_dir()).await.is_ok()) {
+      return proj_dirs.config_dir().to_path_buf();
+    }
+  };
+  PathBuf::from(r"./")
+}
+
+impl Config {
+  pub async fn path(try_make: bool) -> PathBuf {
+    config_dir(try_make).await.join("config.json")
+  }
+
+  pub async fn load() -> Result<Config, LoadError> {
+    use tokio::fs;
+    use tokio::io::AsyncReadExt;
+
+    let mut config_file = fs::File::open(Config::path(false).await)
+      .await
+      .map_err(|_| LoadError::NoSuchFile)?;
+
+    let mut config_string = String::new();
+    config_file.read_to_string(&mut config_string)
+      .await
+      .map_err(|_| LoadError::ReadError)?;
+
+    serde_json::from_str::<Config>(&config_string).map_err(|_| LoadError::FormatError)
+  }
+
+  pub async fn save(self) -> Result<(), SaveError> {
+    use tokio::fs;
+    use tokio::io::AsyncWriteExt;
+
+    let json = serde_json::to_string_pretty(&self)
+      .map_err(|_| SaveError::FormatError)?;
+
+    let mut file = fs::File::create(Config::path(true).await)
+      .await
+      .map_err(|_| SaveError::FileError)?;
+
+    file.write_all(json.as_bytes())
+      .await
+      .map_err(|_| SaveError::WriteError)
+  }
+}
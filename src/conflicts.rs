@@ -0,0 +1,345 @@
+use std::{path::PathBuf, collections::{HashMap, BTreeMap}, io, fmt::Display};
+
+use crate::mods::ModEntry;
+use crate::util::parse_csv;
+
+/**
+ * Extensions of files under `data/` that Starsector merges across mods rather than picking a single copy of.
+ */
+const MERGED_EXTENSIONS: [&str; 3] = ["json", "csv", "faction"];
+
+/**
+ * Files every mod ships that never affect the game or are always merged harmlessly, so reporting them is just noise.
+ */
+const IGNORED_FILES: [&str; 2] = ["mod_info.json", "data/config/version/version_files.csv"];
+
+/**
+ * Spreadsheets the game merges row by row, keyed on the given column, so a later mod's row silently replaces an earlier one's.
+ */
+const ROW_KEYED_FILES: [(&str, &str); 11] = [
+  ("data/hulls/ship_data.csv", "id"),
+  ("data/hulls/wing_data.csv", "id"),
+  ("data/weapons/weapon_data.csv", "id"),
+  ("data/hullmods/hull_mods.csv", "id"),
+  ("data/shipsystems/ship_systems.csv", "id"),
+  ("data/characters/skills/skill_data.csv", "id"),
+  ("data/campaign/commodities.csv", "id"),
+  ("data/campaign/special_items.csv", "id"),
+  ("data/campaign/industries.csv", "id"),
+  ("data/campaign/market_conditions.csv", "id"),
+  ("data/campaign/rules.csv", "id"),
+];
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub enum ConflictKind {
+  /**
+   * The game merges both copies, so both mods' changes apply unless they touch the same rows or keys.
+   */
+  Merged,
+  /**
+   * Only one copy is used, either because the file type can't be merged or because a mod lists it in its `replace` array.
+   */
+  Replaced,
+}
+
+impl Display for ConflictKind {
+  fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::result::Result<(), std::fmt::Error> {
+    match self {
+      ConflictKind::Merged => write!(f, "Merged"),
+      ConflictKind::Replaced => write!(f, "Replaced"),
+    }
+  }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord)]
+pub struct FileConflict {
+  pub path: String,
+  pub kind: ConflictKind,
+}
+
+/**
+ * The game-relevant files a mod ships, as lowercase relative paths with `/` separators, since the game doesn't care about case.
+ */
+#[derive(Debug, Clone)]
+pub struct ModFiles {
+  pub id: String,
+  pub replace: Vec<String>,
+  pub files: Vec<String>,
+  /**
+   * The row IDs defined in each of the `ROW_KEYED_FILES` the mod ships.
+   */
+  pub rows: HashMap<String, Vec<String>>,
+}
+
+impl ModFiles {
+  pub fn scan(entry: &ModEntry) -> io::Result<ModFiles> {
+    let mut relative_paths = vec![];
+    collect_files(&entry.path, &entry.path, &mut relative_paths)?;
+
+    let mut files = vec![];
+    let mut rows = HashMap::new();
+    for relative in relative_paths {
+      let file = normalize(&relative.to_string_lossy());
+      if IGNORED_FILES.contains(&file.as_str()) {
+        continue;
+      }
+
+      if let Some((_, key)) = ROW_KEYED_FILES.iter().find(|(keyed, _)| *keyed == file) {
+        // Unreadable or malformed spreadsheets just aren't analyzed, the game will complain about them loudly enough
+        if let Ok(text) = std::fs::read_to_string(entry.path.join(&relative)) {
+          rows.insert(file.clone(), row_ids(&text, key));
+        }
+      }
+      files.push(file);
+    }
+
+    Ok(ModFiles {
+      id: entry.id.clone(),
+      replace: entry.replace.iter().map(|path| normalize(path)).collect(),
+      files,
+      rows,
+    })
+  }
+
+  /**
+   * Scans every given mod, skipping any whose folder can't be read.
+   */
+  pub fn scan_all(entries: Vec<ModEntry>) -> Vec<ModFiles> {
+    entries.iter()
+      .filter_map(|entry| ModFiles::scan(entry).ok())
+      .collect()
+  }
+}
+
+fn normalize(path: &str) -> String {
+  path.replace('\\', "/").trim_start_matches("./").trim_start_matches('/').to_lowercase()
+}
+
+fn collect_files(root: &PathBuf, dir: &PathBuf, files: &mut Vec<PathBuf>) -> io::Result<()> {
+  for entry in dir.read_dir()? {
+    let entry = entry?;
+    let file_type = entry.file_type()?;
+    if file_type.is_dir() {
+      if entry.file_name() != ".git" {
+        collect_files(root, &entry.path(), files)?;
+      }
+    } else if file_type.is_file() {
+      if let Ok(relative) = entry.path().strip_prefix(root) {
+        files.push(relative.to_path_buf());
+      }
+    }
+  }
+
+  Ok(())
+}
+
+/**
+ * Reads the values of the `key` column, skipping blank IDs and `#` comment rows as the game does.
+ */
+fn row_ids(text: &str, key: &str) -> Vec<String> {
+  let mut records = parse_csv(text).into_iter();
+  let column = match records.next().and_then(|header| header.iter().position(|name| name.trim() == key)) {
+    Some(column) => column,
+    None => return vec![]
+  };
+
+  records
+    .filter(|record| !matches!(record.first(), Some(first) if first.trim_start().starts_with('#')))
+    .filter_map(|record| record.get(column).map(|id| id.trim().to_string()))
+    .filter(|id| !id.is_empty() && !id.starts_with('#'))
+    .collect()
+}
+
+fn conflict_kind(path: &str, left: &ModFiles, right: &ModFiles) -> ConflictKind {
+  let replaced = left.replace.iter().chain(right.replace.iter()).any(|replace| replace == path);
+  let mergeable = path.starts_with("data/") && MERGED_EXTENSIONS.iter().any(|ext| path.ends_with(&format!(".{}", ext)));
+
+  if mergeable && !replaced {
+    ConflictKind::Merged
+  } else {
+    ConflictKind::Replaced
+  }
+}
+
+/**
+ * A spreadsheet row defined by more than one mod. The game loads mods in order of their ID, so the last mod listed wins.
+ */
+#[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord)]
+pub struct RowOverride {
+  pub file: String,
+  pub id: String,
+  pub mods: Vec<String>,
+}
+
+impl RowOverride {
+  pub fn winner(&self) -> &String {
+    self.mods.last().expect("Overridden row has at least two mods")
+  }
+}
+
+/**
+ * Every file shipped by more than one of the analyzed mods, grouped by the pair of mods (ordered by ID) that share it,
+ * along with the spreadsheet rows those mods both define.
+ */
+#[derive(Debug, Clone, Default)]
+pub struct ConflictReport {
+  pub pairs: BTreeMap<(String, String), Vec<FileConflict>>,
+  pub rows: Vec<RowOverride>,
+}
+
+impl ConflictReport {
+  pub fn new<'a>(mods: impl IntoIterator<Item = &'a ModFiles>) -> Self {
+    let mut mods: Vec<&ModFiles> = mods.into_iter().collect();
+    mods.sort_by(|left, right| left.id.cmp(&right.id));
+
+    let mut owners: HashMap<&str, Vec<&ModFiles>> = HashMap::new();
+    for mod_files in mods.iter().copied() {
+      // Root level files aren't loaded by the game, so only files inside a folder are considered
+      for file in mod_files.files.iter().filter(|file| file.contains('/')) {
+        owners.entry(file.as_str()).or_default().push(mod_files);
+      }
+    }
+
+    let mut pairs: BTreeMap<(String, String), Vec<FileConflict>> = BTreeMap::new();
+    for (path, mut owners) in owners.into_iter().filter(|(_, owners)| owners.len() > 1) {
+      owners.sort_by(|left, right| left.id.cmp(&right.id));
+
+      for (idx, left) in owners.iter().enumerate() {
+        for right in &owners[idx + 1..] {
+          pairs.entry((left.id.clone(), right.id.clone()))
+            .or_default()
+            .push(FileConflict {
+              path: path.to_string(),
+              kind: conflict_kind(path, left, right),
+            });
+        }
+      }
+    }
+    pairs.values_mut().for_each(|conflicts| conflicts.sort());
+
+    let mut definitions: BTreeMap<(&str, &str), Vec<String>> = BTreeMap::new();
+    for mod_files in mods.iter() {
+      for (file, ids) in &mod_files.rows {
+        for id in ids {
+          let definers = definitions.entry((file.as_str(), id.as_str())).or_default();
+          if !definers.contains(&mod_files.id) {
+            definers.push(mod_files.id.clone());
+          }
+        }
+      }
+    }
+    let rows = definitions.into_iter()
+      .filter(|(_, definers)| definers.len() > 1)
+      .map(|((file, id), definers)| RowOverride {
+        file: file.to_string(),
+        id: id.to_string(),
+        mods: definers,
+      })
+      .collect();
+
+    ConflictReport {
+      pairs,
+      rows
+    }
+  }
+
+  pub fn is_empty(&self) -> bool {
+    self.pairs.is_empty() && self.rows.is_empty()
+  }
+
+  /**
+   * The conflicts involving the given mod, keyed by the other mod's ID.
+   */
+  pub fn conflicts_for(&self, id: &str) -> Vec<(&String, &Vec<FileConflict>)> {
+    self.pairs.iter()
+      .filter_map(|((left, right), conflicts)| if left == id {
+        Some((right, conflicts))
+      } else if right == id {
+        Some((left, conflicts))
+      } else {
+        None
+      })
+      .collect()
+  }
+
+  /**
+   * The most severe kind of conflict the given mod is involved in, if any. Defining an overridden row counts as a replacement.
+   */
+  pub fn worst_conflict(&self, id: &str) -> Option<ConflictKind> {
+    if self.rows.iter().any(|row| row.mods.iter().any(|definer| definer == id)) {
+      return Some(ConflictKind::Replaced);
+    }
+
+    self.conflicts_for(id)
+      .into_iter()
+      .flat_map(|(_, conflicts)| conflicts.iter().map(|conflict| conflict.kind))
+      .max()
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  fn mod_files(id: &str, files: &[&str], replace: &[&str], rows: &[(&str, &[&str])]) -> ModFiles {
+    ModFiles {
+      id: id.to_string(),
+      replace: replace.iter().map(|path| path.to_string()).collect(),
+      files: files.iter().map(|path| path.to_string()).collect(),
+      rows: rows.iter().map(|(file, ids)| (file.to_string(), ids.iter().map(|id| id.to_string()).collect())).collect(),
+    }
+  }
+
+  #[test]
+  fn shared_files() {
+    let a = mod_files("a", &["graphics/ship.png", "data/config/settings.json", "readme.txt"], &[], &[]);
+    let b = mod_files("b", &["graphics/ship.png", "data/config/settings.json", "readme.txt"], &[], &[]);
+
+    let report = ConflictReport::new(vec![&b, &a]);
+
+    assert_eq!(report.pairs.len(), 1);
+    assert_eq!(report.pairs[&("a".to_string(), "b".to_string())], vec![
+      FileConflict { path: "data/config/settings.json".to_string(), kind: ConflictKind::Merged },
+      FileConflict { path: "graphics/ship.png".to_string(), kind: ConflictKind::Replaced },
+    ]);
+    assert_eq!(report.worst_conflict("a"), Some(ConflictKind::Replaced));
+  }
+
+  #[test]
+  fn replaced_files() {
+    let a = mod_files("a", &["data/config/settings.json"], &["data/config/settings.json"], &[]);
+    let b = mod_files("b", &["data/config/settings.json"], &[], &[]);
+
+    let report = ConflictReport::new(vec![&a, &b]);
+
+    assert_eq!(report.conflicts_for("b"), vec![(&"a".to_string(), &vec![
+      FileConflict { path: "data/config/settings.json".to_string(), kind: ConflictKind::Replaced },
+    ])]);
+  }
+
+  #[test]
+  fn row_overrides() {
+    let file = "data/hulls/ship_data.csv";
+    let c = mod_files("c", &[file], &[], &[(file, &["hound", "lasher"])]);
+    let a = mod_files("a", &[file], &[], &[(file, &["hound"])]);
+    let b = mod_files("b", &[file], &[], &[(file, &["hound", "wolf"])]);
+
+    let report = ConflictReport::new(vec![&c, &a, &b]);
+
+    assert_eq!(report.rows, vec![RowOverride {
+      file: file.to_string(),
+      id: "hound".to_string(),
+      mods: vec!["a".to_string(), "b".to_string(), "c".to_string()],
+    }]);
+    assert_eq!(report.rows[0].winner(), "c");
+    assert_eq!(report.worst_conflict("a"), Some(ConflictKind::Replaced));
+  }
+
+  #[test]
+  fn row_ids_skip_comments_and_blanks() {
+    let csv = "\u{feff}name,id,desc\r\n\"Hound, Mk. II\",hound,\"A \"\"fast\"\"\nfrigate\"\r\n#Lasher,lasher,\r\nWolf,#wolf,\r\n,,\r\nKite,kite\r\n";
+
+    assert_eq!(row_ids(csv, "id"), vec!["hound", "kite"]);
+    assert!(row_ids(csv, "missing").is_empty());
+  }
+}
@@ -1,9 +1,8 @@
 use std::path::PathBuf;
-use iced::{Application, button, Button, Column, Command, Element, Length, Row, Text, executor, Clipboard, Container, Space, Subscription};
+use iced::{Application, button, Button, Column, Command, Element, Length, Row, Text, executor, Clipboard, Container, Space, Subscription, Scrollable, scrollable};
 use iced_aw::{modal, Modal, Card};
 
-use serde::{Serialize, Deserialize};
-use serde_json;
+use serde::Deserialize;
 
 use lazy_static::lazy_static;
 
@@ -37,13 +36,21 @@ mod installer;
 
 use crate::style;
 
-use settings::{SettingsMessage, vmparams::{VMParams, Value, Unit}};
-use mod_list::{ModListMessage, ModEntryMessage};
+use starsector_mod_manager::util::get_starsector_version;
+use starsector_mod_manager::vmparams::{VMParams, Value, Unit};
+pub use starsector_mod_manager::{LoadError, SaveError};
+use starsector_mod_manager::config::Config;
+use starsector_mod_manager::installer::DownloadOptions;
+use starsector_mod_manager::profiles::Profiles;
+
+use settings::SettingsMessage;
+use mod_list::{ModListMessage, ModEntryMessage, ProfilesMessage, RecentlyRemovedMessage};
 
 #[derive(Default)]
 struct ModalState {
   cancel_state: button::State,
   accept_state: button::State,
+  changelog_scroll: scrollable::State,
 }
 
 
@@ -98,6 +105,7 @@ impl Application for App {
       },
       Command::batch(vec![
         Command::perform(Config::load(), Message::ConfigLoaded),
+        Command::perform(Profiles::load(), |res| Message::ModListMessage(ModListMessage::ProfilesMessage(ProfilesMessage::Loaded(res)))),
         Command::perform(App::get_latest_manager(), Message::TagsReceived)
       ])
     )
@@ -132,14 +140,22 @@ impl Application for App {
             commands.push(self.settings.update(SettingsMessage::InitRoot(config.install_dir.clone())).map(|m| Message::SettingsMessage(m)));
             commands.push(self.settings.update(SettingsMessage::GitWarnToggled(config.git_warn)).map(|m| Message::SettingsMessage(m)));
             commands.push(self.settings.update(SettingsMessage::ResolutionChanged(resolution)).map(|m| Message::SettingsMessage(m)));
+            commands.push(self.settings.update(SettingsMessage::TrashRetentionChanged(config.trash_retention_days.to_string())).map(|m| Message::SettingsMessage(m)));
+            commands.push(self.settings.update(SettingsMessage::MaxConcurrentInstallsChanged(config.max_concurrent_installs.to_string())).map(|m| Message::SettingsMessage(m)));
+            commands.push(self.settings.update(SettingsMessage::DownloadRetriesChanged(config.download_retries.to_string())).map(|m| Message::SettingsMessage(m)));
+            commands.push(self.settings.update(SettingsMessage::DownloadTimeoutChanged(config.download_timeout_secs.to_string())).map(|m| Message::SettingsMessage(m)));
+            commands.push(self.settings.update(SettingsMessage::ArchiveCacheSizeChanged(config.archive_cache_mb.to_string())).map(|m| Message::SettingsMessage(m)));
 
+            commands.push(self.mod_list.update(ModListMessage::SetUpdateHolds(config.update_holds.clone())).map(|m| Message::ModListMessage(m)));
             commands.push(self.mod_list.update(ModListMessage::SetRoot(config.install_dir.clone())).map(|m| Message::ModListMessage(m)));
             commands.push(self.mod_list.update(ModListMessage::SetLastBrowsed(config.last_browsed.clone())).map(|m| Message::ModListMessage(m)));
+            commands.push(self.mod_list.update(ModListMessage::SetInstallLimit(config.max_concurrent_installs as usize)).map(|m| Message::ModListMessage(m)));
+            commands.push(self.mod_list.update(ModListMessage::SetDownloadOptions(DownloadOptions::from_config(&config))).map(|m| Message::ModListMessage(m)));
             self.mod_list.git_warn = config.git_warn;
 
             if let Some(install_dir) = &config.install_dir {
               commands.push(Command::perform(VMParams::load(install_dir.clone()), Message::VMParamsLoaded));
-              commands.push(Command::perform(App::get_starsector_version(install_dir.clone()), Message::VersionLoaded))
+              commands.push(Command::perform(get_starsector_version(install_dir.clone()), Message::VersionLoaded))
             }
 
             self.config = Some(config);
@@ -150,15 +166,16 @@ impl Application for App {
             commands.push(self.settings.update(SettingsMessage::InitRoot(None)).map(|m| Message::SettingsMessage(m)));
 
             self.config = Some(Config {
-              install_dir: None,
-              last_browsed: None,
-              git_warn: false,
-              experimental_launch: false,
-              experimental_resolution: (1280, 768)
+              ..Default::default()
             })
           }
         }
 
+        if let Some(config) = &self.config {
+          let purge = ModListMessage::RecentlyRemovedMessage(RecentlyRemovedMessage::Purge(config.trash_retention_days));
+          commands.push(self.mod_list.update(purge).map(|m| Message::ModListMessage(m)));
+        }
+
         Command::batch(commands)
       },
       Message::ConfigSaved(res) => {
@@ -191,7 +208,7 @@ impl Application for App {
       Message::SettingsOpen => {
         self.settings_open = true;
 
-        return Command::none();
+        self.settings.update(SettingsMessage::LoadCache).map(|m| Message::SettingsMessage(m))
       },
       Message::SettingsApply(keep_open) => {
         self.settings_open = keep_open;
@@ -209,6 +226,21 @@ impl Application for App {
           config.git_warn = self.settings.git_warn;
           config.experimental_launch = self.settings.experimental_launch;
           config.experimental_resolution = self.settings.experimental_resolution;
+          config.trash_retention_days = self.settings.trash_retention_days;
+          if config.max_concurrent_installs != self.settings.max_concurrent_installs {
+            config.max_concurrent_installs = self.settings.max_concurrent_installs;
+            commands.push(self.mod_list.update(ModListMessage::SetInstallLimit(config.max_concurrent_installs as usize)).map(|m| Message::ModListMessage(m)));
+          }
+          config.download_retries = self.settings.download_retries;
+          config.download_timeout_secs = self.settings.download_timeout_secs;
+          if config.archive_cache_mb != self.settings.archive_cache_mb {
+            config.archive_cache_mb = self.settings.archive_cache_mb;
+            commands.push(self.settings.update(SettingsMessage::PruneCache).map(|m| Message::SettingsMessage(m)));
+          }
+          commands.push(self.mod_list.update(ModListMessage::SetDownloadOptions(DownloadOptions::from_config(config))).map(|m| Message::ModListMessage(m)));
+
+          let purge = ModListMessage::RecentlyRemovedMessage(RecentlyRemovedMessage::Purge(config.trash_retention_days));
+          commands.push(self.mod_list.update(purge).map(|m| Message::ModListMessage(m)));
 
           commands.push(Command::perform(config.clone().save(), Message::ConfigSaved));
 
@@ -234,7 +266,7 @@ impl Application for App {
               }
 
               commands.push(Command::perform(vmparams.clone().save(install_dir.clone()), Message::VMParamsSaved));
-              commands.push(Command::perform(App::get_starsector_version(install_dir.clone()), Message::VersionLoaded))
+              commands.push(Command::perform(get_starsector_version(install_dir.clone()), Message::VersionLoaded))
             } else {
               commands.push(Command::perform(VMParams::load(install_dir.clone()), Message::VMParamsLoaded))
             }
@@ -244,7 +276,7 @@ impl Application for App {
         Command::batch(commands)
       } 
       Message::SettingsMessage(settings_message) => {
-        if let SettingsMessage::OpenNativeFilePick | SettingsMessage::PathChanged(_) | SettingsMessage::VMParamChanged(_, _) | SettingsMessage::UnitChanged(_, _) | SettingsMessage::GitWarnToggled(_) = settings_message {
+        if let SettingsMessage::OpenNativeFilePick | SettingsMessage::PathChanged(_) | SettingsMessage::VMParamChanged(_, _) | SettingsMessage::UnitChanged(_, _) | SettingsMessage::GitWarnToggled(_) | SettingsMessage::TrashRetentionChanged(_) | SettingsMessage::MaxConcurrentInstallsChanged(_) | SettingsMessage::DownloadRetriesChanged(_) | SettingsMessage::DownloadTimeoutChanged(_) | SettingsMessage::ArchiveCacheSizeChanged(_) = settings_message {
           self.settings_changed = true;
         };
         if let SettingsMessage::OpenReleases = settings_message {
@@ -253,9 +285,16 @@ impl Application for App {
         if let SettingsMessage::GitWarnToggled(val) = settings_message {
           self.mod_list.git_warn = val;
         }
+        if let SettingsMessage::InstallCached(idx) = settings_message {
+          if let Some(path) = self.settings.cached_archive_path(idx) {
+            return Command::batch(vec![
+              self.mod_list.update(ModListMessage::InstallPressed(mod_list::InstallOptions::FromCache(path))).map(|m| Message::ModListMessage(m)),
+              self.settings.update(settings_message).map(|m| Message::SettingsMessage(m))
+            ]);
+          }
+        }
 
-        self.settings.update(settings_message);
-        return Command::none();
+        return self.settings.update(settings_message).map(|m| Message::SettingsMessage(m));
       },
       Message::ModListMessage(mod_list_message) => {
         let mut commands = vec![self.mod_list.update(mod_list_message.clone()).map(|m| Message::ModListMessage(m))];
@@ -303,6 +342,7 @@ impl Application for App {
 
         if let Some(config) = self.config.as_mut() {
           config.last_browsed = self.mod_list.last_browsed.clone();
+          config.update_holds = self.mod_list.update_holds.clone();
 
           commands.push(Command::perform(config.clone().save(), Message::ConfigSaved));
         }
@@ -354,10 +394,11 @@ impl Application for App {
     let mut buttons: Row<Message> = Row::new()
       .push(Space::with_width(Length::Units(5)));
 
-    let starsector_version = Container::new::<Element<Message>>(if let Some(version) = self.mod_list.get_game_version() {
-      Text::new(format!("Starsector Version:   {}", version)).into()
-    } else {
-      Space::with_width(Length::Shrink).into()
+    let starsector_version = Container::new::<Element<Message>>(match (self.mod_list.get_game_version(), self.mod_list.get_active_profile()) {
+      (Some(version), Some(profile)) => Text::new(format!("Starsector Version:   {}      Profile:   {}", version, profile)).into(),
+      (Some(version), None) => Text::new(format!("Starsector Version:   {}", version)).into(),
+      (None, Some(profile)) => Text::new(format!("Profile:   {}", profile)).into(),
+      (None, None) => Space::with_width(Length::Shrink).into()
     }).align_x(iced::Align::Center);
     // let versions = Column::with_children(vec![
     //   update.into(),
@@ -522,6 +563,18 @@ impl Application for App {
                   } else {
                     "{Error: Failed to retrieve remote version}"
                   })).into(),
+                  match entry.as_ref().and_then(|entry| entry.changelog.as_ref()) {
+                    Some(Ok(changelog)) => Column::new()
+                      .push(Text::new("Changelog:"))
+                      .push(
+                        Scrollable::new(&mut state.changelog_scroll)
+                          .push(Text::new(changelog.clone()).size(16))
+                          .max_height(200)
+                      )
+                      .into(),
+                    Some(Err(_)) => Text::new("The changelog for this update could not be retrieved.").into(),
+                    None => Space::with_height(Length::Shrink).into()
+                  },
                   Text::new("WARNING:").color(iced::Color::from_rgb8(0xB0, 0x00, 0x20)).into(),
                   Text::new("Save compatibility is not guaranteed when updating a mod. Your save may no longer load if you apply this update.").into(),
                   Text::new("Bug reports about saves broken by using this feature will be ignored.").into(),
@@ -624,130 +677,4 @@ impl App {
       Err(format!("Could not find any releases."))
     }
   }
-
-  async fn get_starsector_version(install_dir: PathBuf) -> Result<String, LoadError> {
-    use std::io::Read;
-    use classfile_parser::class_parser;
-    use tokio::{task, fs};
-    use if_chain::if_chain;
-    use regex::bytes::Regex;
-
-    let install_dir_clone = install_dir.clone();
-    let res = task::spawn_blocking(move || {
-      let mut zip = zip::ZipArchive::new(std::fs::File::open(install_dir_clone.join("starsector-core").join("starfarer_obf.jar")).unwrap()).unwrap();
-
-      // println!("{:?}", zip.file_names().collect::<Vec<&str>>());
-      
-      let mut version_class = zip.by_name("com/fs/starfarer/Version.class").map_err(|_| LoadError::NoSuchFile)?;
-
-      let mut buf: Vec<u8> = Vec::new();
-      version_class.read_to_end(&mut buf)
-        .map_err(|_| LoadError::ReadError)
-        .and_then(|_| {
-          class_parser(&buf).map_err(|_| LoadError::FormatError).map(|(_, class_file)| class_file)
-        })
-        .and_then(|class_file| {
-          class_file.fields.iter().find_map(|f| {
-            if_chain! {
-              if let classfile_parser::constant_info::ConstantInfo::Utf8(name) =  &class_file.const_pool[(f.name_index - 1) as usize];
-              if name.utf8_string == "versionOnly";
-              if let Ok((_, attr)) = classfile_parser::attribute_info::constant_value_attribute_parser(&f.attributes.first().unwrap().info);
-              if let classfile_parser::constant_info::ConstantInfo::Utf8(utf_const) = &class_file.const_pool[attr.constant_value_index as usize];
-              then {
-                return Some(utf_const.utf8_string.clone())
-              } else {
-                None
-              }
-            }
-          }).ok_or_else(|| LoadError::FormatError)
-        })
-    }).await
-    .map_err(|_| LoadError::ReadError)
-    .flatten();
-
-    if res.is_err() {
-      lazy_static! {
-        static ref RE: Regex = Regex::new(r"Starting Starsector (.*) launcher").unwrap();
-      }
-      fs::read(install_dir.join("starsector-core").join("starsector.log")).await
-        .map_err(|_| LoadError::ReadError)
-        .and_then(|file| {
-          RE.captures(&file)
-            .and_then(|captures| captures.get(1))
-            .ok_or(LoadError::FormatError)
-            .and_then(|m| String::from_utf8(m.as_bytes().to_vec()).map_err(|_| LoadError::FormatError))
-        })
-    } else {
-      res
-    }
-  }
-}
-
-#[derive(Debug, Clone, Serialize, Deserialize)]
-pub enum LoadError {
-  NoSuchFile,
-  ReadError,
-  FormatError
-}
-
-#[derive(Debug, Clone)]
-pub enum SaveError {
-  FileError,
-  WriteError,
-  FormatError,
-}
-
-#[derive(Debug, Clone, Serialize, Deserialize)]
-pub struct Config {
-  install_dir: Option<PathBuf>,
-  last_browsed: Option<PathBuf>,
-  git_warn: bool,
-  experimental_launch: bool,
-  experimental_resolution: (u32, u32),
-}
-
-impl Config {
-  async fn path(try_make: bool) -> PathBuf {
-    use directories::ProjectDirs;
-    use tokio::fs;
-
-    if let Some(proj_dirs) = ProjectDirs::from("org", "laird", "Starsector Mod Manager") {
-      if proj_dirs.config_dir().exists() || (try_make && fs::create_dir_all(proj_dirs.config_dir()).await.is_ok()) {
-        return proj_dirs.config_dir().to_path_buf().join("config.json");
-      }
-    };
-    PathBuf::from(r"./config.json")
-  }
-
-  async fn load() -> Result<Config, LoadError> {
-    use tokio::fs;
-    use tokio::io::AsyncReadExt;
-
-    let mut config_file = fs::File::open(Config::path(false).await)
-      .await
-      .map_err(|_| LoadError::NoSuchFile)?;
-
-    let mut config_string = String::new();
-    config_file.read_to_string(&mut config_string)
-      .await
-      .map_err(|_| LoadError::ReadError)?;
-
-    serde_json::from_str::<Config>(&config_string).map_err(|_| LoadError::FormatError)
-  }
-
-  async fn save(self) -> Result<(), SaveError> {
-    use tokio::fs;
-    use tokio::io::AsyncWriteExt;
-
-    let json = serde_json::to_string_pretty(&self)
-      .map_err(|_| SaveError::FormatError)?;
-
-    let mut file = fs::File::create(Config::path(true).await)
-      .await
-      .map_err(|_| SaveError::FileError)?;
-
-    file.write_all(json.as_bytes())
-      .await
-      .map_err(|_| SaveError::WriteError)
-  }
 }
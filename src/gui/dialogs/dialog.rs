@@ -7,6 +7,8 @@ pub trait DialogInterface {
 
   fn query(message: String) -> bool;
 
+  fn input(title: &str, message: &str) -> Option<String>;
+
   fn select_folder(title: &str, path: &str) -> Option<PathBuf>;
 
   fn select_archives(path: &str) -> Option<Vec<PathBuf>>;
@@ -20,6 +20,10 @@ impl DialogInterface for Dialog {
       tfd::YesNo::No => false
     }
   }
+
+  fn input(title: &str, message: &str) -> Option<String> {
+    tfd::input_box(title, &sanitise(message.to_string()), "")
+  }
   
   fn select_folder(title: &str, path: &str) -> Option<PathBuf> {
     tfd::select_folder_dialog(title, path)
@@ -1,6 +1,7 @@
 use std::path::PathBuf;
 
 use native_dialog::{MessageDialog, MessageType, FileDialog};
+use tinyfiledialogs as tfd;
 
 mod dialog;
 pub use self::dialog::*;
@@ -77,6 +78,11 @@ impl DialogInterface for Dialog {
     res
   }
 
+  // native-dialog has no text input, so this falls back to tinyfiledialogs
+  fn input(title: &str, message: &str) -> Option<String> {
+    tfd::input_box(title, message, "")
+  }
+
   fn select_folder(_: &str, path: &str) -> Option<PathBuf> {
     FileDialog::new().set_location(path)
       .show_open_single_dir()
@@ -1,26 +1,12 @@
 use std::path::PathBuf;
 use std::hash::{Hash, Hasher};
-use std::{
-  fs::{copy, create_dir_all, read_dir},
-  io, io::Write
-};
-use std::sync::Arc;
+use std::sync::{Arc, atomic::{AtomicBool, Ordering}};
 use iced_futures::futures::{self, future, StreamExt};
-use tokio::{
-  sync::mpsc,
-  task,
-  fs::rename
-};
-use compress_tools;
-use tempfile::{tempdir, TempDir};
-use snafu::{Snafu, ResultExt, OptionExt};
-// use find_mountpoint::find_mountpoint;
-use remove_dir_all::remove_dir_all;
-use infer;
-use unrar;
-use if_chain::if_chain;
-
-use super::mod_list::ModEntry;
+use tokio::sync::{mpsc, Semaphore, OwnedSemaphorePermit, Notify};
+
+use starsector_mod_manager::installer::{self, ProgressReporter, InstallError, DownloadOptions, ArchiveChecksum};
+use starsector_mod_manager::mods::ModEntry;
+pub use starsector_mod_manager::installer::{HybridPath, InstallProgress};
 
 #[derive(Clone)]
 pub struct Installation<I> 
@@ -30,14 +16,36 @@ where
   pub id: I,
   payload: Payload,
   mods_dir: PathBuf,
-  installed: Vec<String>
+  installed: Vec<String>,
+  /**
+   * Shared by every installation, so only so many jobs extract or copy at once however many installations are running.
+   */
+  limit: Arc<Semaphore>,
+  cancelled: Vec<Arc<AtomicBool>>,
+  /**
+   * Wakes queued jobs when one of them is cancelled, so they don't wait for a free slot just to find out.
+   */
+  cancel_notify: Arc<Notify>,
+  download_options: DownloadOptions,
 }
 
 #[derive(Clone)]
 pub enum Payload {
   Initial(Vec<PathBuf>),
   Resumed(String, HybridPath, PathBuf),
-  Download(String, String, PathBuf)
+  Download(String, String, PathBuf, ArchiveChecksum),
+  /**
+   * Several updates confirmed together, each as for `Download`.
+   */
+  Downloads(Vec<(String, String, PathBuf, ArchiveChecksum)>),
+  /**
+   * An archive at a URL given by the user, installed as if it had been picked from disk.
+   */
+  Url(String),
+  /**
+   * Mods already staged from an archive or folder holding several, which the user picked from.
+   */
+  Staged(Vec<(HybridPath, ModEntry)>),
 }
 
 impl From<Vec<PathBuf>> for Payload {
@@ -52,9 +60,9 @@ impl From<(String, HybridPath, PathBuf)> for Payload {
   }
 }
 
-impl From<(String, String, PathBuf)> for Payload {
-  fn from((url, target_version, old_path): (String, String, PathBuf)) -> Self {
-    Payload::Download(url, target_version, old_path)
+impl From<(String, String, PathBuf, ArchiveChecksum)> for Payload {
+  fn from((url, target_version, old_path, checksum): (String, String, PathBuf, ArchiveChecksum)) -> Self {
+    Payload::Download(url, target_version, old_path, checksum)
   }
 }
 
@@ -62,12 +70,40 @@ impl<I> Installation<I>
 where
   I: 'static + Hash + Copy + Send,
 {
-  pub fn new<T: Into<Payload>>(id: I, payload: T, mods_dir: PathBuf, installed: Vec<String>) -> Self {
+  pub fn new<T: Into<Payload>>(id: I, payload: T, mods_dir: PathBuf, installed: Vec<String>, limit: Arc<Semaphore>) -> Self {
+    let payload = payload.into();
+    let jobs = match &payload {
+      Payload::Initial(paths) => paths.len(),
+      Payload::Staged(mods) => mods.len(),
+      Payload::Downloads(updates) => updates.len(),
+      _ => 1
+    };
+
     Installation {
       id,
-      payload: payload.into(),
+      payload,
       mods_dir,
-      installed
+      installed,
+      limit,
+      cancelled: (0..jobs).map(|_| Arc::new(AtomicBool::new(false))).collect(),
+      cancel_notify: Arc::new(Notify::new()),
+      download_options: DownloadOptions::default(),
+    }
+  }
+
+  pub fn download_options(mut self, download_options: DownloadOptions) -> Self {
+    self.download_options = download_options;
+
+    self
+  }
+
+  /**
+   * Stops the given job as soon as possible. A job that hasn't started yet never will.
+   */
+  pub fn cancel(&self, job: usize) {
+    if let Some(cancelled) = self.cancelled.get(job) {
+      cancelled.store(true, Ordering::Relaxed);
+      self.cancel_notify.notify_waiters();
     }
   }
 
@@ -96,322 +132,425 @@ where
     _input: futures::stream::BoxStream<'static, I>,
   ) -> futures::stream::BoxStream<'static, Self::Output> {
     let id = self.id;
+    let limit = self.limit;
+    let cancelled = self.cancelled;
+    let cancel_notify = self.cancel_notify;
+    let download_options = self.download_options;
 
     Box::pin(futures::stream::unfold(
       State::Ready(self.payload, self.mods_dir, self.installed),
-      move |state| async move {
-        match state {
-          State::Ready(payload, mods_dir, installed) => {
-            let (tx, rx) = mpsc::unbounded_channel();
-
-            match payload {
-              Payload::Initial(paths) => {
-                for path in paths {
-                  let task_tx = tx.clone();
-                  let mods_dir = mods_dir.clone();
-                  let installed = installed.clone();
-  
+      move |state| {
+        let limit = limit.clone();
+        let cancelled = cancelled.clone();
+        let cancel_notify = cancel_notify.clone();
+
+        async move {
+          match state {
+            State::Ready(payload, mods_dir, installed) => {
+              let (tx, rx) = mpsc::unbounded_channel();
+
+              let labels = match payload {
+                Payload::Initial(paths) => {
+                  let labels = paths.iter()
+                    .map(|path| path.file_name().map_or_else(|| path.display().to_string(), |name| name.to_string_lossy().to_string()))
+                    .collect();
+
+                  for (index, path) in paths.into_iter().enumerate() {
+                    let job = Job::new(index, &tx, &cancelled, &cancel_notify, &limit);
+                    let mods_dir = mods_dir.clone();
+                    let installed = installed.clone();
+    
+                    tokio::spawn(async move {
+                      handle_path(job, path, mods_dir, installed).await;
+                    });
+                  }
+
+                  labels
+                },
+                Payload::Resumed(name, new_path, old_path) => {
+                  let labels = vec![format!("Replacing {}", name)];
+                  let job = Job::new(0, &tx, &cancelled, &cancel_notify, &limit);
                   tokio::spawn(async move {
-                    handle_path(task_tx, path, mods_dir, installed).await;
+                    handle_delete(job, name, new_path, old_path).await;
                   });
-                }
-              },
-              Payload::Resumed(name, new_path, old_path) => {
-                tokio::spawn(async move {
-                  handle_delete(tx, name, new_path, old_path).await;
-                });
-              },
-              Payload::Download(url, target_version, old_path) => {
-                tokio::spawn(async move {
-                  handle_auto(tx, url, target_version, old_path, mods_dir).await;
-                });
-              }
-            }
 
-            Some((
-              None,
-              State::Installing {
-                receiver: rx,
-                complete: vec![],
-                errored: vec![]
-              }
-            ))
-          },
-          State::Installing {
-            mut receiver,
-            mut complete,
-            mut errored
-          } => match receiver.recv().await {
-            Some(ChannelMessage::Success(mod_name)) => {
-              complete.push(mod_name);
+                  labels
+                },
+                Payload::Download(url, target_version, old_path, checksum) => {
+                  let labels = vec![format!("Updating {}", old_path.file_name().map_or_else(|| url.clone(), |name| name.to_string_lossy().to_string()))];
+                  let job = Job::new(0, &tx, &cancelled, &cancel_notify, &limit);
+                  tokio::spawn(async move {
+                    handle_auto(job, url, target_version, old_path, checksum, download_options).await;
+                  });
 
-              Some((
-                Some(Progress::Finished),
-                State::Installing {
-                  receiver,
-                  complete,
-                  errored
-                }
-              ))
-            },
-            Some(ChannelMessage::Duplicate(name, id, new_path, old_path)) => {
-              Some((
-                Some(Progress::Query(name, id, new_path, old_path)),
-                State::Installing {
-                  receiver,
-                  complete,
-                  errored
+                  labels
+                },
+                Payload::Downloads(updates) => {
+                  let labels = updates.iter()
+                    .map(|(url, _, old_path, _)| format!("Updating {}", old_path.file_name().map_or_else(|| url.clone(), |name| name.to_string_lossy().to_string())))
+                    .collect();
+
+                  for (index, (url, target_version, old_path, checksum)) in updates.into_iter().enumerate() {
+                    let job = Job::new(index, &tx, &cancelled, &cancel_notify, &limit);
+                    tokio::spawn(async move {
+                      handle_auto(job, url, target_version, old_path, checksum, download_options).await;
+                    });
+                  }
+
+                  labels
+                },
+                Payload::Url(url) => {
+                  let labels = vec![url.rsplit('/').find(|segment| !segment.is_empty()).unwrap_or(&url).to_string()];
+                  let job = Job::new(0, &tx, &cancelled, &cancel_notify, &limit);
+                  tokio::spawn(async move {
+                    handle_url(job, url, mods_dir, installed, download_options).await;
+                  });
+
+                  labels
+                },
+                Payload::Staged(mods) => {
+                  let labels = mods.iter().map(|(_, mod_info)| mod_info.name.clone()).collect();
+
+                  for (index, staged) in mods.into_iter().enumerate() {
+                    let job = Job::new(index, &tx, &cancelled, &cancel_notify, &limit);
+                    let mods_dir = mods_dir.clone();
+                    let installed = installed.clone();
+
+                    tokio::spawn(async move {
+                      let _permit = match job.start().await {
+                        Some(permit) => permit,
+                        None => return
+                      };
+
+                      handle_staged(job, staged, mods_dir, installed).await;
+                    });
+                  }
+
+                  labels
                 }
-              ))
-            },
-            Some(ChannelMessage::Error(mod_name)) => {
-              errored.push(mod_name);
+              };
 
               Some((
-                None,
+                Some(Progress::Started(id, labels)),
                 State::Installing {
-                  receiver,
-                  complete,
-                  errored
+                  receiver: rx,
+                  complete: vec![],
+                  errored: vec![]
                 }
               ))
             },
-            None => {
-              Some((
-                Some(Progress::Completed(id, complete, errored)),
-                State::Finished
-              ))
+            State::Installing {
+              mut receiver,
+              mut complete,
+              mut errored
+            } => match receiver.recv().await {
+              Some(ChannelMessage::Success(mod_name)) => {
+                complete.push(mod_name);
+
+                Some((
+                  Some(Progress::Finished),
+                  State::Installing {
+                    receiver,
+                    complete,
+                    errored
+                  }
+                ))
+              },
+              Some(ChannelMessage::Bundle(source, mods)) => {
+                Some((
+                  Some(Progress::Bundle(source, mods)),
+                  State::Installing {
+                    receiver,
+                    complete,
+                    errored
+                  }
+                ))
+              },
+              Some(ChannelMessage::Duplicate(name, id, new_path, old_path)) => {
+                Some((
+                  Some(Progress::Query(name, id, new_path, old_path)),
+                  State::Installing {
+                    receiver,
+                    complete,
+                    errored
+                  }
+                ))
+              },
+              Some(ChannelMessage::Status(job, status)) => {
+                Some((
+                  Some(Progress::Status(id, job, status)),
+                  State::Installing {
+                    receiver,
+                    complete,
+                    errored
+                  }
+                ))
+              },
+              Some(ChannelMessage::Error(mod_name)) => {
+                errored.push(mod_name);
+
+                Some((
+                  None,
+                  State::Installing {
+                    receiver,
+                    complete,
+                    errored
+                  }
+                ))
+              },
+              None => {
+                Some((
+                  Some(Progress::Completed(id, complete, errored)),
+                  State::Finished
+                ))
+              }
+            },
+            State::Finished => {
+              None
             }
-          },
-          State::Finished => {
-            None
-          }
+        }
         }
       },
     ).filter_map(|prog| future::ready(prog)))
   }
 }
 
-async fn handle_path(tx: mpsc::UnboundedSender<ChannelMessage>, path: PathBuf, mods_dir: PathBuf, installed: Vec<String>) {
-  let mut mod_folder = if path.is_file() {
-    let decompress = task::spawn_blocking(move || decompress(path)).await.expect("Run decompression");
-    match decompress {
-      Ok(temp) => HybridPath::Temp(Arc::new(temp), None),
-      Err(err) => {
-        println!("{:?}", err);
-        tx.send(ChannelMessage::Error(err.to_string())).expect("Send error over async channel");
+/**
+ * A single archive, folder or download within an installation, along with what it needs to report back and be cancelled.
+ * Each job's sender is dropped once it's done, which is how the installation knows every job has finished.
+ */
+struct Job {
+  index: usize,
+  tx: mpsc::UnboundedSender<ChannelMessage>,
+  cancelled: Arc<AtomicBool>,
+  cancel_notify: Arc<Notify>,
+  limit: Arc<Semaphore>,
+}
 
-        return;
-      }
+impl Job {
+  fn new(
+    index: usize,
+    tx: &mpsc::UnboundedSender<ChannelMessage>,
+    cancelled: &[Arc<AtomicBool>],
+    cancel_notify: &Arc<Notify>,
+    limit: &Arc<Semaphore>
+  ) -> Self {
+    Job {
+      index,
+      tx: tx.clone(),
+      cancelled: cancelled.get(index).cloned().unwrap_or_default(),
+      cancel_notify: cancel_notify.clone(),
+      limit: limit.clone(),
     }
-  } else {
-    HybridPath::PathBuf(path)
-  };
+  }
 
-  let dir = mod_folder.get_path_copy();
-  if_chain! {
-    if let Ok(maybe_path) = task::spawn_blocking(move || find_nested_mod(&dir)).await.expect("Find mod in given folder");
-    if let Some(mod_path) = maybe_path;
-    if let Ok(mod_info) = ModEntry::from_file(&mod_path);
-    then {
-      if let Some(id) = installed.into_iter().find(|existing| **existing == mod_info.id) {
-        mod_folder = match mod_folder {
-          HybridPath::PathBuf(_) => HybridPath::PathBuf(mod_path.clone()),
-          HybridPath::Temp(temp, _) => HybridPath::Temp(temp, Some(mod_path.clone()))
-        };
-
-        tx.send(ChannelMessage::Duplicate(mod_info.name, id, mod_folder, None)).expect("Send query over async channel");
-      } else if !mods_dir.join(mod_info.id.clone()).exists() {
-        move_or_copy(mod_path, mods_dir.join(mod_info.id)).await;
-
-        tx.send(ChannelMessage::Success(mod_info.name)).expect("Send success over async channel");
-      } else {
-        mod_folder = match mod_folder {
-          HybridPath::PathBuf(_) => HybridPath::PathBuf(mod_path.clone()),
-          HybridPath::Temp(temp, _) => HybridPath::Temp(temp, Some(mod_path.clone()))
-        };
-
-        tx.send(ChannelMessage::Duplicate(mod_info.name, String::new(), mod_folder, Some(mods_dir.join(mod_info.id.clone())))).expect("Send query over async channel");
+  /**
+   * Waits for a free slot in the install queue. Returns `None` if the job was cancelled while it waited.
+   */
+  async fn start(&self) -> Option<OwnedSemaphorePermit> {
+    loop {
+      // Created before checking the flag so a cancellation in between still wakes us
+      let notified = self.cancel_notify.notified();
+      if self.cancelled.load(Ordering::Relaxed) {
+        self.status(JobStatus::Cancelled);
+
+        return None
       }
-    } else {
-      tx.send(ChannelMessage::Error(format!("Could not find mod folder or parse mod_info file."))).expect("Send error over async channel");
-    }
-  }
-}
 
-fn decompress(path: PathBuf) -> Result<TempDir, InstallError> {
-  let source = std::fs::File::open(&path).context(Io {})?;
-  let temp_dir = tempdir().context(Io {})?;
-  let mime_type = infer::get_from_path(&path)
-    .context(Io {})?
-    .context(Mime { detail: "Failed to get mime type"})?
-    .mime_type();
-
-  match mime_type {
-    "application/vnd.rar" | "application/x-rar-compressed" => {
-      #[cfg(not(target_env="musl"))]
-      unrar::Archive::new(path.to_string_lossy().to_string())
-        .extract_to(temp_dir.path().to_string_lossy().to_string())
-        .ok().context(Unrar { detail: "Opaque Unrar error. Assume there's been an error unpacking your rar archive." })?
-        .process()
-        .ok().context(Unrar { detail: "Opaque Unrar error. Assume there's been an error unpacking your rar archive." })?;
-        // trust me I tried to de-dupe this and it's buggered
-      #[cfg(target_env="musl")]
-      compress_tools::uncompress_archive(source, temp_dir.path(), compress_tools::Ownership::Preserve).context(CompressTools {})?
-    }
-    _ => {
-      compress_tools::uncompress_archive(source, temp_dir.path(), compress_tools::Ownership::Preserve).context(CompressTools {})?
-    }
-  }
+      let acquire = self.limit.clone().acquire_owned();
+      futures::pin_mut!(notified, acquire);
+      if let future::Either::Left((permit, _)) = future::select(acquire, notified).await {
+        let permit = permit.ok()?;
+        if self.cancelled.load(Ordering::Relaxed) {
+          self.status(JobStatus::Cancelled);
 
-  Ok(temp_dir)
-}
+          return None
+        }
 
-fn find_nested_mod(dest: &PathBuf) -> std::io::Result<Option<PathBuf>> {
-  for entry in read_dir(dest)? {
-    let entry = entry?;
-    if entry.file_type()?.is_dir() {
-      let res = find_nested_mod(&entry.path())?;
-      if res.is_some() {
-        return Ok(res);
-      }
-    } else if entry.file_type()?.is_file() {
-      if entry.file_name() == "mod_info.json" {
-        return Ok(Some(dest.to_path_buf()));
+        return Some(permit)
       }
     }
   }
 
-  Ok(None)
-}
-
-async fn move_or_copy(from: PathBuf, to: PathBuf) {
-  // let mount_from = find_mountpoint(&from).expect("Find origin mount point");
-  // let mount_to = find_mountpoint(&to).expect("Find destination mount point");
+  /**
+   * Forwards installer progress for this job over the channel, and lets the installer see if the job has been cancelled.
+   */
+  fn reporter(&self) -> ProgressReporter {
+    let tx = self.tx.clone();
+    let index = self.index;
+
+    ProgressReporter::new(move |progress| {
+      // The receiver only goes away once the installation is over, at which point progress is irrelevant
+      let _ = tx.send(ChannelMessage::Status(index, JobStatus::Running(progress)));
+    }).cancellable(self.cancelled.clone())
+  }
 
-  if let Err(_) = rename(from.clone(), to.clone()).await {
-    task::spawn_blocking(move || copy_dir_recursive(&to, &from)).await
-      .expect("Run blocking dir copy")
-      .expect("Copy dir to new destination");
+  fn status(&self, status: JobStatus) {
+    self.tx.send(ChannelMessage::Status(self.index, status)).expect("Send status over async channel");
   }
-}
 
-fn copy_dir_recursive(to: &PathBuf, from: &PathBuf) -> io::Result<()> {
-  if !to.exists() {
-    create_dir_all(to)?;
+  fn succeed(&self, name: String) {
+    self.status(JobStatus::Done);
+    self.tx.send(ChannelMessage::Success(name)).expect("Send success over async channel");
   }
 
-  for entry in from.read_dir()? {
-    let entry = entry?;
-    if entry.file_type()?.is_dir() {
-      copy_dir_recursive(&to.to_path_buf().join(entry.file_name()), &entry.path())?;
-    } else if entry.file_type()?.is_file() {
-      copy(entry.path(), &to.to_path_buf().join(entry.file_name()))?;
+  /**
+   * A cancelled job isn't an error as far as the user is concerned, so it only updates the job's status.
+   */
+  fn fail(&self, err: InstallError, message: String) {
+    if let InstallError::Cancelled = err {
+      self.status(JobStatus::Cancelled);
+    } else {
+      self.status(JobStatus::Failed);
+      self.tx.send(ChannelMessage::Error(message)).expect("Send error over async channel");
     }
   }
-
-  Ok(())
 }
 
-async fn handle_delete(tx: mpsc::UnboundedSender<ChannelMessage>, name: String, new_path: HybridPath, old_path: PathBuf) {
-  let destination = old_path.canonicalize().expect("Canonicalize destination");
-  remove_dir_all(destination).expect("Remove old mod");
-
-  let origin = new_path.get_path_copy();
-  move_or_copy(origin, old_path).await;
+async fn handle_path(job: Job, path: PathBuf, mods_dir: PathBuf, installed: Vec<String>) {
+  let _permit = match job.start().await {
+    Some(permit) => permit,
+    None => return
+  };
 
-  tx.send(ChannelMessage::Success(name)).expect("Send success over async channel");
+  let source = path.file_name().map_or_else(|| path.display().to_string(), |name| name.to_string_lossy().to_string());
+  let found = installer::stage_all(path, job.reporter()).await;
+  handle_found(job, source, found, mods_dir, installed).await
 }
 
-async fn handle_auto(tx: mpsc::UnboundedSender<ChannelMessage>, url: String, target_version: String, old_path: PathBuf, _: PathBuf) {
-  match download(url).await {
-    Ok(file) => {
-      let path = file.path().to_path_buf();
-      let decompress = task::spawn_blocking(move || decompress(path)).await.expect("Run decompression");
-      match decompress {
-        Ok(temp) => {
-          let hybrid = HybridPath::Temp(Arc::new(temp), None);
-          let path = hybrid.get_path_copy();
-          if_chain! {
-            if let Ok(Some(path)) = task::spawn_blocking(move || find_nested_mod(&path)).await.expect("Run blocking search").context(Io {});
-            if let Ok(mod_info) = ModEntry::from_file(&path);
-            then {
-              let hybrid = if let HybridPath::Temp(temp, _) = hybrid {
-                HybridPath::Temp(temp, Some(path))
-              } else {
-                unreachable!()
-              };
-              if mod_info.version_checker.map(|v| v.version.to_string()) != Some(target_version) {
-                tx.send(ChannelMessage::Error(format!("Downloaded version does not match expected version"))).expect("Send error over async channel");
-              } else {
-                handle_delete(tx, mod_info.name, hybrid, old_path).await;
-              }
-            } else {
-              tx.send(ChannelMessage::Error(format!("Some kind of unpack error"))).expect("Send error over async channel");
-            }
-          }
-        },
-        Err(err) => {
-          println!("{:?}", err);
-          tx.send(ChannelMessage::Error(err.to_string())).expect("Send error over async channel");
+async fn handle_url(job: Job, url: String, mods_dir: PathBuf, installed: Vec<String>, download_options: DownloadOptions) {
+  let _permit = match job.start().await {
+    Some(permit) => permit,
+    None => return
+  };
 
-          return;
-        }
-      };
+  let source = url.clone();
+  let found = match installer::download(url, &ArchiveChecksum::default(), download_options, job.reporter()).await {
+    // The archive is extracted by staging, so the download can go as soon as that's done
+    Ok(file) => installer::stage_all(file.path().to_path_buf(), job.reporter()).await,
+    Err(err) => Err(err)
+  };
+  handle_found(job, source, found, mods_dir, installed).await
+}
+
+/**
+ * Installs a lone mod straight away, while an archive or folder holding several is handed to the user to pick from.
+ */
+async fn handle_found(job: Job, source: String, found: Result<Vec<(HybridPath, ModEntry)>, InstallError>, mods_dir: PathBuf, installed: Vec<String>) {
+  match found {
+    Ok(mut mods) if mods.len() == 1 => handle_staged(job, mods.remove(0), mods_dir, installed).await,
+    Ok(mods) => {
+      job.status(JobStatus::AwaitingConfirmation);
+      job.tx.send(ChannelMessage::Bundle(source, mods)).expect("Send bundle over async channel");
     },
     Err(err) => {
-      tx.send(ChannelMessage::Error(err.to_string())).expect("Send error over async channel");
+      let message = err.to_string();
+      job.fail(err, message)
     }
   }
 }
 
-async fn download(url: String) -> Result<tempfile::NamedTempFile, InstallError> {
-  let mut file = tempfile::NamedTempFile::new().context(Io {})?;
-  let mut res = reqwest::get(url).await.context(Network {})?;
+/**
+ * Installs a staged mod, unless it's already installed or its folder is taken, in which case the user is asked what to do.
+ */
+async fn handle_staged(job: Job, (mod_folder, mod_info): (HybridPath, ModEntry), mods_dir: PathBuf, installed: Vec<String>) {
+  if let Some(id) = installed.into_iter().find(|existing| **existing == mod_info.id) {
+    job.status(JobStatus::AwaitingConfirmation);
+    job.tx.send(ChannelMessage::Duplicate(mod_info.name, id, mod_folder, None)).expect("Send query over async channel");
+  } else if !mods_dir.join(mod_info.id.clone()).exists() {
+    match installer::install_to(mod_folder, mods_dir.join(mod_info.id), job.reporter()).await {
+      Ok(()) => job.succeed(mod_info.name),
+      Err(err) => {
+        let message = format!("{}: {}", mod_info.name, err);
+        job.fail(err, message)
+      }
+    }
+  } else {
+    job.status(JobStatus::AwaitingConfirmation);
+    job.tx.send(ChannelMessage::Duplicate(mod_info.name, String::new(), mod_folder, Some(mods_dir.join(mod_info.id.clone())))).expect("Send query over async channel");
+  }
+}
 
-  while let Some(chunk) = res.chunk().await.context(Network {})? {
-    file.write(&chunk).context(Io {})?;
+async fn handle_delete(job: Job, name: String, new_path: HybridPath, old_path: PathBuf) {
+  let _permit = match job.start().await {
+    Some(permit) => permit,
+    None => return
   };
 
-  Ok(file)
+  match installer::replace(new_path, old_path, job.reporter()).await {
+    Ok(()) => job.succeed(name),
+    Err(err) => {
+      let message = format!("{}: {}", name, err);
+      job.fail(err, message)
+    }
+  }
 }
 
-#[derive(Debug, Clone)]
-pub enum HybridPath {
-  PathBuf(PathBuf),
-  Temp(Arc<TempDir>, Option<PathBuf>),
-}
+async fn handle_auto(job: Job, url: String, target_version: String, old_path: PathBuf, checksum: ArchiveChecksum, download_options: DownloadOptions) {
+  let _permit = match job.start().await {
+    Some(permit) => permit,
+    None => return
+  };
 
-impl HybridPath {
-  fn get_path_copy(&self) -> PathBuf {
-    match self {
-      HybridPath::PathBuf(ref path) => path.clone(),
-      HybridPath::Temp(_, Some(ref path)) => path.clone(),
-      HybridPath::Temp(ref arc, None) => arc.path().to_path_buf()
+  match installer::auto_update(url, target_version, old_path, checksum, download_options, job.reporter()).await {
+    Ok(name) => job.succeed(name),
+    Err(err) => {
+      let message = err.to_string();
+      job.fail(err, message)
     }
   }
 }
 
-#[derive(Debug, Snafu)]
-enum InstallError {
-  Io { source: std::io::Error },
-  Mime { detail: String },
-  CompressTools { source: compress_tools::Error },
-  Unrar { detail: String },
-  Network { source: reqwest::Error },
-  Any { detail: String }
-}
-
 #[derive(Debug, Clone)]
 pub enum Progress<I> 
 where
   I: 'static + Hash + Copy + Send,
 {
+  Started(I, Vec<String>),
+  Status(I, usize, JobStatus),
   Completed(I, Vec<String>, Vec<String>),
   Query(String, String, HybridPath, Option<PathBuf>),
+  Bundle(String, Vec<(HybridPath, ModEntry)>),
   Finished
 }
 
+/**
+ * Where a single job, i.e. one archive, folder or download, within an installation is at.
+ */
+#[derive(Debug, Clone)]
+pub enum JobStatus {
+  Pending,
+  Running(InstallProgress),
+  AwaitingConfirmation,
+  /**
+   * Cancellation has been requested, but the job hasn't stopped yet.
+   */
+  Cancelling,
+  Cancelled,
+  Done,
+  Failed,
+}
+
+impl JobStatus {
+  pub fn is_active(&self) -> bool {
+    matches!(self, JobStatus::Pending | JobStatus::Running(_))
+  }
+}
+
+impl std::fmt::Display for JobStatus {
+  fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+    match self {
+      JobStatus::Pending => write!(f, "Queued"),
+      JobStatus::Running(progress) => write!(f, "{}", progress),
+      JobStatus::AwaitingConfirmation => write!(f, "Waiting for confirmation"),
+      JobStatus::Cancelling => write!(f, "Cancelling"),
+      JobStatus::Cancelled => write!(f, "Cancelled"),
+      JobStatus::Done => write!(f, "Done"),
+      JobStatus::Failed => write!(f, "Failed"),
+    }
+  }
+}
+
 pub enum State {
   Ready(Payload, PathBuf, Vec<String>),
   Installing {
@@ -424,7 +563,9 @@ pub enum State {
 
 #[derive(Debug, Clone)]
 pub enum ChannelMessage {
+  Status(usize, JobStatus),
   Success(String),
   Duplicate(String, String, HybridPath, Option<PathBuf>),
+  Bundle(String, Vec<(HybridPath, ModEntry)>),
   Error(String)
 }
@@ -1,37 +1,36 @@
 use std::{
-  io::{Read, BufReader, BufRead},
-  path::PathBuf, collections::HashMap,
-  fs::File,
-  fmt::Display
+  path::PathBuf, collections::{HashMap, HashSet}, sync::Arc,
 };
 use iced::{
   Text, Column, Command, Element, Length, Row, Scrollable, scrollable, Button,
   button, Checkbox, Container, Rule, PickList, pick_list, Space, Tooltip,
-  tooltip, Subscription, TextInput, text_input, Align
+  tooltip, Subscription, TextInput, text_input, Align, ProgressBar
 };
-use serde::{Serialize, Deserialize};
-use json_comments::strip_comments;
-use json5;
-use handwritten_json;
-use if_chain::if_chain;
 use opener;
 use sublime_fuzzy::best_match;
-use lazy_static::lazy_static;
-use regex::Regex;
+use tokio::sync::Semaphore;
 
-lazy_static! {
-  static ref VERSION_REGEX: Regex = Regex::new(r"\.|a-RC|A-RC|a-rc|a").unwrap();
-}
-
-use serde_aux::prelude::*;
+use starsector_mod_manager::{mods, ModRepository};
+use starsector_mod_manager::mods::{EnabledMods, Dependency, DependencyStatus, parse_game_version};
+pub use starsector_mod_manager::mods::{UpdateStatus, UpdateHold, ModVersionMeta};
+use starsector_mod_manager::util::{get_master_version, cached_master_versions, get_changelog, format_timestamp};
+use starsector_mod_manager::version_cache::{self, CachedVersion};
+use starsector_mod_manager::conflicts::{ModFiles, ConflictReport, ConflictKind};
+use starsector_mod_manager::installer::{uninstall, DownloadOptions, ArchiveChecksum, ArchivePreview};
 
-use crate::gui::installer::{self, Installation};
+use crate::gui::installer::{self, Installation, JobStatus};
 use crate::style;
 use crate::gui::SaveError;
 use crate::gui::util;
 
 mod headings;
 use headings::{Headings, HeadingsMessage};
+mod profiles;
+pub use profiles::ProfilesMessage;
+use profiles::ProfileControls;
+mod recently_removed;
+pub use recently_removed::RecentlyRemovedMessage;
+use recently_removed::RecentlyRemoved;
 
 pub struct ModList {
   root_dir: Option<PathBuf>,
@@ -51,11 +50,42 @@ pub struct ModList {
   headings: Headings,
   installs: Vec<Installation<u16>>,
   installation_id: u16,
+  install_jobs: Vec<InstallJob>,
+  bundles: Vec<Bundle>,
+  previews: Vec<Preview>,
+  /**
+   * Mods left out of a batch update because they have no direct download, by the installation doing the rest of the batch.
+   */
+  manual_updates: HashMap<u16, Vec<String>>,
+  /**
+   * Mods whose updates shouldn't be offered, by ID. Kept in step with the config, which the application saves.
+   */
+  pub update_holds: HashMap<String, UpdateHold>,
+  install_limit: Arc<Semaphore>,
+  /**
+   * How many permits `install_limit` is meant to have, whether or not they're currently taken.
+   */
+  install_limit_size: usize,
+  download_options: DownloadOptions,
+  /**
+   * Version checks still running, and what those that finished fetched. The cache is saved once they're all done.
+   */
+  pending_version_checks: usize,
+  version_cache_updates: HashMap<String, CachedVersion>,
   search_state: text_input::State,
   search_query: Option<String>,
   pub starsector_version: (Option<String>, Option<String>, Option<String>, Option<String>),
   pub git_warn: bool,
   launch_button_state: button::State,
+  profile_controls: ProfileControls,
+  mod_files: HashMap<String, ModFiles>,
+  conflicts: ConflictReport,
+  bottom_pane: BottomPane,
+  conflicts_scroll: scrollable::State,
+  bottom_pane_close_state: button::State,
+  recently_removed: RecentlyRemoved,
+  selected: HashSet<String>,
+  multi_select: bool,
 }
 
 #[derive(Debug, Clone)]
@@ -67,15 +97,80 @@ pub enum ModListMessage {
   InstallPressed(InstallOptions),
   ToolsPressed(ToolOptions),
   EnabledModsSaved(Result<(), SaveError>),
+  ProfileSwitched(String, Result<(), SaveError>),
   InstallationComplete(u16, Vec<String>, Vec<String>),
+  InstallationStarted(u16, Vec<String>),
+  InstallJobStatus(u16, usize, JobStatus),
+  CancelInstallJob(u16, usize),
+  SetInstallLimit(usize),
+  InstallLimitLowered,
+  SetDownloadOptions(DownloadOptions),
+  SetUpdateHolds(HashMap<String, UpdateHold>),
   DuplicateMod(String, String, installer::HybridPath, Option<PathBuf>),
+  BundleFound(String, Vec<(installer::HybridPath, mods::ModEntry)>),
+  BundleToggled(usize, usize, bool),
+  BundleInstall(usize),
+  BundleDismiss(usize),
+  PreviewLoaded(PathBuf, Result<ArchivePreview, String>),
+  PreviewInstall(usize),
+  PreviewDismiss(usize),
   SingleInstallComplete,
-  MasterVersionReceived((String, Result<ModVersionMeta, String>)),
+  MasterVersionReceived((String, Result<ModVersionMeta, String>, Option<CachedVersion>)),
+  VersionCacheLoaded(Vec<ModVersionMeta>, HashMap<String, CachedVersion>),
+  VersionCacheSaved,
+  ChangelogReceived((String, Result<String, String>)),
   ParseModListError(()),
   HeadingsMessage(HeadingsMessage),
   SearchChanged(String),
   SetVersion(String),
   LaunchStarsector,
+  ProfilesMessage(ProfilesMessage),
+  ModFilesScanned(Vec<ModFiles>),
+  CloseBottomPane,
+  ModifiersChanged(bool),
+  UninstallComplete(Vec<(String, Result<(), String>)>),
+  RecentlyRemovedMessage(RecentlyRemovedMessage),
+}
+
+/**
+ * A single archive, folder or download being installed, as shown in the install queue.
+ */
+struct InstallJob {
+  installation: u16,
+  job: usize,
+  label: String,
+  status: JobStatus,
+  cancel_state: button::State,
+}
+
+/**
+ * An archive or folder found to hold several mods, waiting for the user to pick which of them to install.
+ */
+struct Bundle {
+  source: String,
+  mods: Vec<(installer::HybridPath, mods::ModEntry, bool)>,
+  install_state: button::State,
+  dismiss_state: button::State,
+}
+
+/**
+ * An archive or folder picked for installation, showing what it holds so the user can confirm before anything is extracted.
+ */
+struct Preview {
+  path: PathBuf,
+  preview: Result<ArchivePreview, String>,
+  install_state: button::State,
+  dismiss_state: button::State,
+}
+
+/**
+ * What the pane below the mod list is showing.
+ */
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum BottomPane {
+  Description,
+  Conflicts,
+  RecentlyRemoved,
 }
 
 impl ModList {
@@ -98,11 +193,30 @@ impl ModList {
       headings: Headings::new().unwrap(),
       installs: vec![],
       installation_id: 0,
+      install_jobs: vec![],
+      bundles: vec![],
+      previews: vec![],
+      manual_updates: HashMap::new(),
+      update_holds: HashMap::new(),
+      install_limit: Arc::new(Semaphore::new(2)),
+      install_limit_size: 2,
+      download_options: DownloadOptions::default(),
+      pending_version_checks: 0,
+      version_cache_updates: HashMap::new(),
       search_state: text_input::State::default(),
       search_query: None,
       starsector_version: (None, None, None, None),
       git_warn: false,
       launch_button_state: button::State::default(),
+      profile_controls: ProfileControls::new(),
+      mod_files: HashMap::new(),
+      conflicts: ConflictReport::default(),
+      bottom_pane: BottomPane::Description,
+      conflicts_scroll: scrollable::State::new(),
+      bottom_pane_close_state: button::State::new(),
+      recently_removed: RecentlyRemoved::new(),
+      selected: HashSet::new(),
+      multi_select: false,
     }
   }
 
@@ -139,37 +253,11 @@ impl ModList {
         if let Some(entry) = self.mods.get_mut(&id) {
           match message {
             ModEntryMessage::EntryHighlighted => {
-              self.mod_description.update(ModDescriptionMessage::ModChanged(entry.clone()));
-
-              entry.update(ModEntryMessage::EntryHighlighted);
-
-              if let Some(key) = &self.currently_highlighted {
-                if !id.eq(key) {
-                  let key = key.clone();
-                  if let Some(old_entry) = self.mods.get_mut(&key) {
-                    old_entry.update(ModEntryMessage::EntryCleared);
-                  }
-                }
-              }
-
-              self.currently_highlighted = Some(id);
+              self.highlight(id);
             },
             ModEntryMessage::EntryCleared => {},
-            ModEntryMessage::ToggleEnabled(_) => {
-              entry.update(message);
-
-              if let Some(path) = &self.root_dir {
-                let enabled_mods = EnabledMods {
-                  enabled_mods: self.mods.iter()
-                    .filter_map(|(id, ModEntry { enabled, .. })| if *enabled {
-                      Some(id.clone())
-                    } else {
-                      None 
-                    })
-                    .collect(),
-                };
-                return Command::perform(enabled_mods.save(path.join("mods").join("enabled_mods.json")), ModListMessage::EnabledModsSaved)
-              }
+            ModEntryMessage::ToggleEnabled(enabled) => {
+              return self.toggle_enabled(id, enabled);
             },
             ModEntryMessage::AutoUpdate => {
               self.update(ModListMessage::ModEntryMessage(id, ModEntryMessage::EntryHighlighted));
@@ -180,6 +268,24 @@ impl ModList {
         Command::none()
       },
       ModListMessage::ModDescriptionMessage(message) => {
+        if let ModDescriptionMessage::Uninstall(id) = message {
+          return self.uninstall_mods(vec![id]);
+        }
+        if let ModDescriptionMessage::SetUpdateHold(id, hold) = message {
+          match &hold {
+            Some(hold) => self.update_holds.insert(id.clone(), hold.clone()),
+            None => self.update_holds.remove(&id),
+          };
+          if let Some(entry) = self.mods.get_mut(&id) {
+            entry.mod_info.set_update_hold(hold);
+            if self.mod_description.mod_entry.as_ref().map_or(false, |shown| shown.id == id) {
+              self.mod_description.mod_entry = Some(entry.mod_info.clone());
+            }
+          }
+
+          return Command::none();
+        }
+
         self.mod_description.update(message);
 
         Command::none()
@@ -199,15 +305,7 @@ impl ModList {
                   self.last_browsed = PathBuf::from(last).parent().map(|p| p.to_path_buf());
                 }
 
-                let mod_ids: Vec<String> = self.mods.iter().map(|(id, _)| id.clone()).collect();
-                self.installs.push(Installation::new(
-                  self.installation_id,
-                  paths,
-                  root_dir.join("mods"),
-                  mod_ids
-                ));
-
-                self.installation_id += 1;
+                return Command::batch(paths.into_iter().map(Self::load_preview));
               }
               Command::none()
             },
@@ -215,34 +313,66 @@ impl ModList {
               match util::select_folder_dialog("Select mod folder:", start_path) {
                 Some(source_path) => {
                   self.last_browsed = PathBuf::from(&source_path).parent().map(|p| p.to_path_buf());
-                  
+
+                  return Self::load_preview(PathBuf::from(source_path));
+                },
+                None => {},
+              }
+
+              Command::none()
+            },
+            InstallOptions::FromUrl => {
+              if let Some(url) = util::input("Install from URL", "Paste a link to a mod archive:") {
+                if url.starts_with("http://") || url.starts_with("https://") {
                   let mod_ids: Vec<String> = self.mods.iter().map(|(id, _)| id.clone()).collect();
                   self.installs.push(Installation::new(
                     self.installation_id,
-                    vec![PathBuf::from(source_path)],
+                    installer::Payload::Url(url),
                     root_dir.join("mods"),
-                    mod_ids
-                  ));
+                    mod_ids,
+                    self.install_limit.clone()
+                  ).download_options(self.download_options));
 
                   self.installation_id += 1;
-                },
-                None => {},
+                } else {
+                  util::error(format!("`{}` is not a web link. Links must start with http:// or https://.", url));
+                }
               }
 
               Command::none()
             },
-            InstallOptions::FromDownload(url, target_version, old_path) => {
+            InstallOptions::FromCache(path) => {
+              let mod_ids: Vec<String> = self.mods.iter().map(|(id, _)| id.clone()).collect();
               self.installs.push(Installation::new(
                 self.installation_id,
-                (url, target_version, old_path),
+                vec![path],
                 root_dir.join("mods"),
-                Vec::new()
+                mod_ids,
+                self.install_limit.clone()
               ));
 
               self.installation_id += 1;
 
               Command::none()
             },
+            InstallOptions::FromDownload(url, target_version, old_path) => {
+              let checksum = self.mods.values()
+                .find(|entry| entry.mod_info.path == old_path)
+                .and_then(|entry| entry.mod_info.remote_version.as_ref())
+                .map(ArchiveChecksum::from)
+                .unwrap_or_default();
+              self.installs.push(Installation::new(
+                self.installation_id,
+                (url, target_version, old_path, checksum),
+                root_dir.join("mods"),
+                Vec::new(),
+                self.install_limit.clone()
+              ).download_options(self.download_options));
+
+              self.installation_id += 1;
+
+              Command::none()
+            },
             _ => Command::none()
           }
         } else {
@@ -258,8 +388,90 @@ impl ModList {
 
         Command::none()
       },
+      ModListMessage::ProfileSwitched(name, res) => {
+        // The profile is only marked active once its mods are actually enabled, as otherwise the two would disagree.
+        match res {
+          Ok(()) => self.profile_controls.update(ProfilesMessage::Switch(name), vec![]).map(ModListMessage::ProfilesMessage),
+          Err(err) => {
+            dbg!("{:?}", err);
+            util::error(format!("Failed to save enabled_mods.json, so profile \"{}\" was not made active.", name));
+
+            Command::none()
+          }
+        }
+      },
+      ModListMessage::InstallationStarted(id, labels) => {
+        self.install_jobs.extend(labels.into_iter().enumerate().map(|(job, label)| InstallJob {
+          installation: id,
+          job,
+          label,
+          status: JobStatus::Pending,
+          cancel_state: button::State::new(),
+        }));
+
+        Command::none()
+      },
+      ModListMessage::InstallJobStatus(id, job, status) => {
+        if let Some(install_job) = self.install_jobs.iter_mut().find(|install_job| install_job.installation == id && install_job.job == job) {
+          // Progress reported before the job noticed it was cancelled shouldn't hide that it's stopping
+          if !(matches!(install_job.status, JobStatus::Cancelling) && status.is_active()) {
+            install_job.status = status;
+          }
+        }
+
+        Command::none()
+      },
+      ModListMessage::CancelInstallJob(id, job) => {
+        if let Some(installation) = self.installs.iter().find(|installation| installation.id == id) {
+          installation.cancel(job);
+        }
+        if let Some(install_job) = self.install_jobs.iter_mut().find(|install_job| install_job.installation == id && install_job.job == job) {
+          if install_job.status.is_active() {
+            install_job.status = JobStatus::Cancelling;
+          }
+        }
+
+        Command::none()
+      },
+      ModListMessage::SetInstallLimit(limit) => {
+        // The semaphore is shared with every queued installation, so it's resized rather than replaced
+        let limit = limit.max(1);
+        let current = std::mem::replace(&mut self.install_limit_size, limit);
+        if limit > current {
+          self.install_limit.add_permits(limit - current);
+
+          Command::none()
+        } else if limit < current {
+          // Permits held by running jobs are taken out of circulation as those jobs finish
+          let semaphore = self.install_limit.clone();
+          let excess = (current - limit) as u32;
+
+          Command::perform(async move {
+            if let Ok(permits) = semaphore.acquire_many_owned(excess).await {
+              permits.forget();
+            }
+          }, |_| ModListMessage::InstallLimitLowered)
+        } else {
+          Command::none()
+        }
+      },
+      ModListMessage::InstallLimitLowered => Command::none(),
+      ModListMessage::SetDownloadOptions(download_options) => {
+        self.download_options = download_options;
+
+        Command::none()
+      },
+      ModListMessage::SetUpdateHolds(update_holds) => {
+        for (id, entry) in self.mods.iter_mut() {
+          entry.mod_info.set_update_hold(update_holds.get(id).cloned());
+        }
+        self.update_holds = update_holds;
+
+        Command::none()
+      },
       ModListMessage::InstallationComplete(id, successful, failed) => {
         self.installs.retain(|i| i.id != id);
+        self.install_jobs.retain(|install_job| install_job.installation != id);
 
         let complete = if successful.len() > 0 {
           format!("Succesfully installed:\n{}\n", successful.join(", "))
@@ -267,19 +479,23 @@ impl ModList {
           String::new()
         };
         let errors = if failed.len() > 0 {
-          format!("Failed to install:\n{}", failed.join(", "))
+          format!("Failed to install:\n{}\n", failed.join(", "))
         } else {
           String::new()
         };
-        if successful.len() > 0 || failed.len() > 0 {
-          util::notif(format!("{}{}", complete, errors));
+        let manual = match self.manual_updates.remove(&id) {
+          Some(manual) if manual.len() > 0 => format!("Must be downloaded and updated manually:\n{}", manual.join(", ")),
+          _ => String::new()
+        };
+        if successful.len() > 0 || failed.len() > 0 || manual.len() > 0 {
+          util::notif(format!("{}{}{}", complete, errors, manual));
         }
 
         Command::batch(self.parse_mod_folder())
       },
       ModListMessage::DuplicateMod(name, id, new_path, old_path) => {
         if let Some(old_path) = old_path {
-          let id = if let Some(mod_id) = self.mods.iter().find_map(|(_, entry)| (entry.path == old_path).then(|| entry.id.clone())) {
+          let id = if let Some(mod_id) = self.mods.iter().find_map(|(_, entry)| (entry.mod_info.path == old_path).then(|| entry.mod_info.id.clone())) {
             format!(", containing mod with ID `{}`, ", mod_id)
           } else {
             String::new()
@@ -296,19 +512,21 @@ impl ModList {
               self.installation_id,
               (name, new_path, old_path),
               PathBuf::new(),
-              vec![]
+              vec![],
+              self.install_limit.clone()
             ));
 
             self.installation_id += 1;
           };
         } else {
-          if let Some((_, entry)) = self.mods.iter().find(|(_, entry)| entry.id == id) {
+          if let Some((_, entry)) = self.mods.iter().find(|(_, entry)| entry.mod_info.id == id) {
             if util::query(format!("A mod with ID `{}`, named `{}`, already exists. Do you want to replace it?\nClicking no will cancel the installation of this mod.", id, name)) {
               self.installs.push(Installation::new(
                 self.installation_id,
-                (name, new_path, entry.path.clone()),
+                (name, new_path, entry.mod_info.path.clone()),
                 self.root_dir.clone().unwrap(),
-                vec![]
+                vec![],
+                self.install_limit.clone()
               ));
 
               self.installation_id += 1;
@@ -318,43 +536,154 @@ impl ModList {
 
         Command::none()
       },
+      ModListMessage::BundleFound(source, found) => {
+        self.bundles.push(Bundle {
+          source,
+          mods: found.into_iter().map(|(folder, mod_info)| (folder, mod_info, true)).collect(),
+          install_state: button::State::new(),
+          dismiss_state: button::State::new(),
+        });
+
+        Command::none()
+      },
+      ModListMessage::BundleToggled(bundle, idx, selected) => {
+        if let Some((_, _, is_selected)) = self.bundles.get_mut(bundle).and_then(|bundle| bundle.mods.get_mut(idx)) {
+          *is_selected = selected;
+        }
+
+        Command::none()
+      },
+      ModListMessage::BundleInstall(bundle) => {
+        if bundle >= self.bundles.len() {
+          return Command::none();
+        }
+
+        let selected: Vec<(installer::HybridPath, mods::ModEntry)> = self.bundles.remove(bundle).mods.into_iter()
+          .filter_map(|(folder, mod_info, selected)| selected.then(|| (folder, mod_info)))
+          .collect();
+        if let (Some(root_dir), false) = (self.root_dir.clone(), selected.is_empty()) {
+          let mod_ids: Vec<String> = self.mods.iter().map(|(id, _)| id.clone()).collect();
+          self.installs.push(Installation::new(
+            self.installation_id,
+            installer::Payload::Staged(selected),
+            root_dir.join("mods"),
+            mod_ids,
+            self.install_limit.clone()
+          ));
+
+          self.installation_id += 1;
+        }
+
+        Command::none()
+      },
+      ModListMessage::BundleDismiss(bundle) => {
+        // Dropping the staged mods cleans up whatever they were extracted to
+        if bundle < self.bundles.len() {
+          self.bundles.remove(bundle);
+        }
+
+        Command::none()
+      },
+      ModListMessage::PreviewLoaded(path, mut preview) => {
+        if let (Ok(preview), Some(root_dir)) = (&mut preview, &self.root_dir) {
+          let mods_dir = root_dir.join("mods");
+          let mods = &self.mods;
+          preview.mark_replacements(|id| mods.get(id)
+            .map(|entry| entry.mod_info.path.clone())
+            .or_else(|| Some(mods_dir.join(id)).filter(|path| path.exists()))
+          );
+        }
+
+        self.previews.push(Preview {
+          path,
+          preview,
+          install_state: button::State::new(),
+          dismiss_state: button::State::new(),
+        });
+
+        Command::none()
+      },
+      ModListMessage::PreviewInstall(preview) => {
+        if preview >= self.previews.len() {
+          return Command::none();
+        }
+
+        let path = self.previews.remove(preview).path;
+        if let Some(root_dir) = self.root_dir.clone() {
+          let mod_ids: Vec<String> = self.mods.iter().map(|(id, _)| id.clone()).collect();
+          self.installs.push(Installation::new(
+            self.installation_id,
+            vec![path],
+            root_dir.join("mods"),
+            mod_ids,
+            self.install_limit.clone()
+          ));
+
+          self.installation_id += 1;
+        }
+
+        Command::none()
+      },
+      ModListMessage::PreviewDismiss(preview) => {
+        if preview < self.previews.len() {
+          self.previews.remove(preview);
+        }
+
+        Command::none()
+      },
       ModListMessage::SingleInstallComplete => {
         Command::batch(self.parse_mod_folder())
       }
-      ModListMessage::MasterVersionReceived((id, res)) => {
-        if_chain! {
-          if let Some(entry) = self.mods.get_mut(&id);
-          if let Some(ModVersionMeta { version: local_version, .. }) = &entry.version_checker;
-          then {
-            match res {
-              Ok(remote_version_meta) => {
-                let version = remote_version_meta.version.clone();
-
-                if version == *local_version {
-                  entry.update_status = Some(UpdateStatus::UpToDate)
-                } else if version < *local_version {
-                  entry.update_status = Some(UpdateStatus::Discrepancy(version))
-                } else if version.major - local_version.major > 0 {
-                  entry.update_status = Some(UpdateStatus::Major(version))
-                } else if version.minor - local_version.minor > 0 {
-                  entry.update_status = Some(UpdateStatus::Minor(version))
-                } else {
-                  entry.update_status = Some(UpdateStatus::Patch(version))
-                };
-                entry.remote_version = Some(remote_version_meta);
-              },
-              Err(_err) => {
-                // debug_println!("Could not get remote update data for {}.\nError: {}", id, err);
-                entry.update_status = Some(UpdateStatus::Error)
-              }
-            }
-          } else {
-            dbg!("Have a remote version file, but either local entry or local version file are missing, which is odd to say the least.");
+      ModListMessage::MasterVersionReceived((id, res, update)) => {
+        let mut commands = vec![];
+
+        self.pending_version_checks = self.pending_version_checks.saturating_sub(1);
+        if let Some(update) = update {
+          self.version_cache_updates.insert(id.clone(), update);
+        }
+        if self.pending_version_checks == 0 {
+          let updates = std::mem::take(&mut self.version_cache_updates);
+          commands.push(Command::perform(version_cache::save(updates), |_| ModListMessage::VersionCacheSaved));
+        }
+
+        if let Some(entry) = self.mods.values_mut().find(|entry| entry.mod_info.has_version_check(&id)) {
+          entry.mod_info.set_master_version(&id, res);
+
+          // Only fetched for fresh results, as a changelog is no use without the update it describes
+          if let (Some(url), None, None) = (entry.mod_info.changelog_url(), &entry.mod_info.changelog, entry.mod_info.remote_cached_at) {
+            commands.push(Command::perform(get_changelog(entry.mod_info.id.clone(), url.clone()), ModListMessage::ChangelogReceived));
           }
-        };
+        }
+
+        Command::batch(commands)
+      },
+      ModListMessage::ChangelogReceived((id, res)) => {
+        if let Some(entry) = self.mods.get_mut(&id) {
+          entry.mod_info.changelog = Some(res);
+
+          if self.mod_description.mod_entry.as_ref().map_or(false, |shown| shown.id == id) {
+            self.mod_description.mod_entry = Some(entry.mod_info.clone());
+          }
+        }
 
         Command::none()
       },
+      ModListMessage::VersionCacheLoaded(versions, cache) => {
+        // Statuses from the last check show straight away, and are replaced as each mod is checked again
+        for (id, remote, fetched_at) in cached_master_versions(&versions, &cache) {
+          if let Some(entry) = self.mods.values_mut().find(|entry| entry.mod_info.has_version_check(&id)) {
+            entry.mod_info.set_cached_master_version(&id, remote, fetched_at);
+          }
+        }
+
+        self.pending_version_checks += versions.len();
+        Command::batch(versions.into_iter().map(|v| {
+          let cached = cache.get(&v.id).cloned();
+
+          Command::perform(get_master_version(v, cached), ModListMessage::MasterVersionReceived)
+        }))
+      },
+      ModListMessage::VersionCacheSaved => Command::none(),
       ModListMessage::ParseModListError(_) => {
         util::error(format!("Failed to parse mods folder. Mod list has not been populated."));
 
@@ -365,12 +694,28 @@ impl ModList {
           ToolOptions::Default => { Command::none() },
           ToolOptions::EnableAll => {
             if let Some(path) = &self.root_dir {
+              // Total conversions can't be used alongside the rest, so they're left out rather than breaking the game
+              let all: Vec<&mods::ModEntry> = self.mods.values().map(|entry| &entry.mod_info).collect();
+              let left_out: HashSet<String> = mods::exclusions(&all).into_iter()
+                .flat_map(|(entry, other)| vec![entry, other])
+                .filter(|entry| entry.total_conversion)
+                .map(|entry| entry.id.clone())
+                .collect();
+              if left_out.len() > 0 {
+                let names: Vec<String> = left_out.iter().filter_map(|id| self.mods.get(id)).map(|entry| entry.mod_info.name.clone()).collect();
+                util::notif(format!("The following total conversions can't be used alongside other content mods, and have been disabled:\n{}", names.join(", ")));
+              }
+
               let mut enabled_mods: Vec<String> = vec![];
               self.mods.iter_mut()
                 .for_each(|(id, entry)| {
-                  enabled_mods.push(id.clone());
-                  entry.update(ModEntryMessage::ToggleEnabled(true));
+                  let enabled = !left_out.contains(id);
+                  if enabled {
+                    enabled_mods.push(id.clone());
+                  }
+                  entry.update(ModEntryMessage::ToggleEnabled(enabled));
                 });
+              self.refresh_mod_state();
 
               Command::perform(EnabledMods { enabled_mods }.save(path.join("mods").join("enabled_mods.json")), ModListMessage::EnabledModsSaved)
             } else {
@@ -383,6 +728,7 @@ impl ModList {
                 .for_each(|(_, entry)| {
                   entry.update(ModEntryMessage::ToggleEnabled(false));
                 });
+              self.refresh_mod_state();
 
               Command::perform(EnabledMods { enabled_mods: vec![] }.save(path.join("mods").join("enabled_mods.json")), ModListMessage::EnabledModsSaved)
             } else {
@@ -392,7 +738,7 @@ impl ModList {
           ToolOptions::FilterDisabled => {
             self.mods.iter_mut()
               .for_each(|(_, entry)| {
-                entry.display = !entry.enabled;
+                entry.display = !entry.mod_info.enabled;
               });
 
             Command::none()
@@ -400,7 +746,7 @@ impl ModList {
           ToolOptions::FilterEnabled => {
             self.mods.iter_mut()
               .for_each(|(_, entry)| {
-                entry.display = entry.enabled;
+                entry.display = entry.mod_info.enabled;
               });
 
             Command::none()
@@ -408,7 +754,7 @@ impl ModList {
           ToolOptions::FilterOutdated => {
             self.mods.iter_mut()
               .for_each(|(_, entry)| {
-                entry.display = matches!(entry.update_status, Some(UpdateStatus::Major(_)) | Some(UpdateStatus::Minor(_)) | Some(UpdateStatus::Patch(_)));
+                entry.display = entry.mod_info.any_update_available();
               });
 
             Command::none()
@@ -416,7 +762,7 @@ impl ModList {
           ToolOptions::FilterError => {
             self.mods.iter_mut()
               .for_each(|(_, entry)| {
-                entry.display = matches!(entry.update_status, Some(UpdateStatus::Error));
+                entry.display = matches!(entry.mod_info.update_status, Some(UpdateStatus::Error));
               });
 
             Command::none()
@@ -424,7 +770,7 @@ impl ModList {
           ToolOptions::FilterUnsupported => {
             self.mods.iter_mut()
               .for_each(|(_, entry)| {
-                entry.display = matches!(entry.update_status, None);
+                entry.display = matches!(entry.mod_info.update_status, None);
               });
 
             Command::none()
@@ -432,11 +778,19 @@ impl ModList {
           ToolOptions::FilterDiscrepancy => {
             self.mods.iter_mut()
               .for_each(|(_, entry)| {
-                entry.display = matches!(entry.update_status, Some(UpdateStatus::Discrepancy(_)));
+                entry.display = matches!(entry.mod_info.update_status, Some(UpdateStatus::Discrepancy(_)));
               });
 
             Command::none()
           }
+          ToolOptions::FilterUtility => {
+            self.mods.iter_mut()
+              .for_each(|(_, entry)| {
+                entry.display = entry.mod_info.utility;
+              });
+
+            Command::none()
+          },
           ToolOptions::FilterNone => {
             self.mods.iter_mut()
               .for_each(|(_, entry)| {
@@ -445,6 +799,31 @@ impl ModList {
 
             Command::none()
           },
+          ToolOptions::ShowConflicts => {
+            self.bottom_pane = BottomPane::Conflicts;
+
+            Command::none()
+          },
+          ToolOptions::ShowRecentlyRemoved => {
+            self.bottom_pane = BottomPane::RecentlyRemoved;
+
+            self.recently_removed.update(RecentlyRemovedMessage::Load).map(ModListMessage::RecentlyRemovedMessage)
+          },
+          ToolOptions::UninstallSelected => {
+            let selected = self.selected.iter().cloned().collect();
+
+            self.uninstall_mods(selected)
+          },
+          ToolOptions::UpdateAll => {
+            let all = self.mods.keys().cloned().collect();
+
+            self.update_mods(all)
+          },
+          ToolOptions::UpdateSelected => {
+            let selected = self.selected.iter().cloned().collect();
+
+            self.update_mods(selected)
+          },
           ToolOptions::Refresh => {
             Command::batch(self.parse_mod_folder())
           }
@@ -493,8 +872,8 @@ impl ModList {
         if search_query.len() > 0 {
           self.mods.iter_mut().for_each(|(id, mod_entry)| {
             let id_score = best_match(&search_query, id).map(|m| m.score());
-            let name_score = best_match(&search_query, &mod_entry.name).map(|m| m.score());
-            let author_score = best_match(&search_query, &mod_entry.author).map(|m| m.score());
+            let name_score = best_match(&search_query, &mod_entry.mod_info.name).map(|m| m.score());
+            let author_score = best_match(&search_query, &mod_entry.mod_info.author).map(|m| m.score());
 
             mod_entry.display = id_score.is_some() || name_score.is_some() || author_score.is_some();
             mod_entry.search_score = std::cmp::max(std::cmp::max(id_score, name_score), author_score);
@@ -510,14 +889,245 @@ impl ModList {
         }
 
         Command::none()
+      },
+      ModListMessage::ModFilesScanned(mod_files) => {
+        self.mod_files = mod_files.into_iter().map(|files| (files.id.clone(), files)).collect();
+        self.refresh_mod_state();
+
+        Command::none()
+      },
+      ModListMessage::CloseBottomPane => {
+        self.bottom_pane = BottomPane::Description;
+
+        Command::none()
+      },
+      ModListMessage::ModifiersChanged(multi_select) => {
+        self.multi_select = multi_select;
+
+        Command::none()
+      },
+      ModListMessage::UninstallComplete(results) => {
+        let (removed, failed): (Vec<_>, Vec<_>) = results.into_iter().partition(|(_, res)| res.is_ok());
+
+        let complete = if removed.len() > 0 {
+          format!("Successfully uninstalled:\n{}\n", removed.into_iter().map(|(name, _)| name).collect::<Vec<String>>().join(", "))
+        } else {
+          String::new()
+        };
+        let errors = if failed.len() > 0 {
+          format!("Failed to uninstall:\n{}", failed.into_iter()
+            .map(|(name, res)| format!("{}: {}", name, res.err().unwrap_or_default()))
+            .collect::<Vec<String>>()
+            .join("\n")
+          )
+        } else {
+          String::new()
+        };
+        util::notif(format!("{}{}", complete, errors));
+
+        Command::batch(self.parse_mod_folder())
+      },
+      ModListMessage::RecentlyRemovedMessage(message) => {
+        let restored = matches!(message, RecentlyRemovedMessage::Restored(Ok(_)));
+        let mut commands = vec![self.recently_removed.update(message).map(ModListMessage::RecentlyRemovedMessage)];
+        if restored {
+          commands.extend(self.parse_mod_folder());
+        }
+
+        Command::batch(commands)
+      },
+      ModListMessage::ProfilesMessage(message) => {
+        if let ProfilesMessage::Switch(name) = &message {
+          let path = if let Some(root_dir) = &self.root_dir {
+            root_dir.join("mods").join("enabled_mods.json")
+          } else {
+            util::error("No install directory set. Please set the Starsector install directory in Settings.");
+            return Command::none();
+          };
+
+          if self.profile_controls.profiles.active.as_ref() == Some(name) {
+            return Command::none();
+          }
+          if self.profile_controls.profiles.is_modified(&self.enabled_ids()) && !util::query(format!(
+            "The currently enabled mods differ from profile \"{}\". Switch to \"{}\" anyway?\nClicking no will keep the current profile.",
+            self.profile_controls.profiles.active.clone().unwrap_or_default(),
+            name
+          )) {
+            return Command::none();
+          }
+
+          let profile = self.profile_controls.profiles.get(name).cloned().unwrap_or_default();
+          let profile_mods: Vec<&mods::ModEntry> = profile.iter().filter_map(|id| self.mods.get(id)).map(|entry| &entry.mod_info).collect();
+          let excluded: Vec<String> = mods::exclusions(&profile_mods).into_iter()
+            .map(|(entry, other)| format!("{} and {}", entry.name, other.name))
+            .collect();
+          if excluded.len() > 0 {
+            util::error(format!(
+              "Profile \"{}\" can't be switched to, as it enables mods that can't be used together, since one of them is a total conversion:\n{}",
+              name,
+              excluded.join("\n")
+            ));
+            return Command::none();
+          }
+
+          self.mods.iter_mut().for_each(|(id, entry)| {
+            entry.update(ModEntryMessage::ToggleEnabled(profile.contains(id)));
+          });
+
+          self.refresh_mod_state();
+
+          let missing: Vec<String> = profile.iter().filter(|id| !self.mods.contains_key(*id)).cloned().collect();
+          if missing.len() > 0 {
+            util::notif(format!("The following mods in this profile are not installed and have been skipped:\n{}", missing.join(", ")));
+          }
+
+          let enabled_mods = EnabledMods { enabled_mods: self.enabled_ids() };
+          let name = name.clone();
+
+          return Command::perform(enabled_mods.save(path), move |res| ModListMessage::ProfileSwitched(name.clone(), res));
+        }
+
+        let enabled_mods = self.enabled_ids();
+        self.profile_controls.update(message, enabled_mods).map(ModListMessage::ProfilesMessage)
       }
     }
   }
 
   pub fn view(&mut self) -> Element<ModListMessage> {
     let install_count = self.mods.len();
-    let active_count = self.mods.values().filter(|entry| entry.enabled).count();
+    let active_count = self.mods.values().filter(|entry| entry.mod_info.enabled).count();
     let starsector_version = self.starsector_version.clone();
+    let install_queue: Vec<Element<ModListMessage>> = self.install_jobs.iter_mut()
+      .map(|install_job| {
+        let bar: Element<ModListMessage> = match &install_job.status {
+          JobStatus::Running(progress) => match progress.fraction() {
+            Some(fraction) => ProgressBar::new(0.0..=1.0, fraction).height(Length::Units(12)).into(),
+            None => Space::with_width(Length::Fill).into()
+          },
+          JobStatus::Done => ProgressBar::new(0.0..=1.0, 1.0).height(Length::Units(12)).into(),
+          _ => ProgressBar::new(0.0..=1.0, 0.0).height(Length::Units(12)).into()
+        };
+
+        let mut cancel = Button::new(&mut install_job.cancel_state, Text::new("Cancel"))
+          .style(style::button_only_hover::Button)
+          .padding(2);
+        if install_job.status.is_active() {
+          cancel = cancel.on_press(ModListMessage::CancelInstallJob(install_job.installation, install_job.job));
+        }
+
+        Row::new()
+          .push(Text::new(install_job.label.clone()).width(Length::FillPortion(3)))
+          .push(Container::new(bar).width(Length::FillPortion(5)))
+          .push(Text::new(install_job.status.to_string()).width(Length::FillPortion(2)))
+          .push(cancel)
+          .spacing(10)
+          .align_items(Align::Center)
+          .into()
+      })
+      .collect();
+    let installed_ids: HashSet<String> = self.mods.keys().cloned().collect();
+    let bundles: Vec<Element<ModListMessage>> = self.bundles.iter_mut()
+      .enumerate()
+      .map(|(bundle_idx, bundle)| {
+        let checklist: Vec<Element<ModListMessage>> = bundle.mods.iter()
+          .enumerate()
+          .map(|(idx, (_, mod_info, selected))| {
+            let label = format!(
+              "{} ({}) {}{}",
+              mod_info.name,
+              mod_info.id,
+              mod_info.version,
+              if installed_ids.contains(&mod_info.id) { " - already installed" } else { "" }
+            );
+
+            Checkbox::new(*selected, label, move |checked| ModListMessage::BundleToggled(bundle_idx, idx, checked)).into()
+          })
+          .collect();
+        let any_selected = bundle.mods.iter().any(|(_, _, selected)| *selected);
+
+        let mut install = Button::new(&mut bundle.install_state, Text::new("Install selected"))
+          .style(style::button_only_hover::Button)
+          .padding(2);
+        if any_selected {
+          install = install.on_press(ModListMessage::BundleInstall(bundle_idx));
+        }
+
+        Column::new()
+          .push(Text::new(format!("Found {} mods in {}. Choose which to install:", bundle.mods.len(), bundle.source)))
+          .push(Row::new()
+            .push(Space::with_width(Length::Units(10)))
+            .push(Column::with_children(checklist).spacing(2))
+          )
+          .push(Row::new()
+            .push(install)
+            .push(Button::new(&mut bundle.dismiss_state, Text::new("Cancel"))
+              .on_press(ModListMessage::BundleDismiss(bundle_idx))
+              .style(style::button_only_hover::Button)
+              .padding(2)
+            )
+            .spacing(10)
+          )
+          .spacing(5)
+          .into()
+      })
+      .collect();
+    let previews: Vec<Element<ModListMessage>> = self.previews.iter_mut()
+      .enumerate()
+      .map(|(preview_idx, preview)| {
+        let source = preview.path.file_name().map_or_else(|| preview.path.display().to_string(), |name| name.to_string_lossy().to_string());
+        let (heading, lines): (String, Vec<Element<ModListMessage>>) = match &preview.preview {
+          Ok(contents) if contents.mods.is_empty() => (
+            format!("No mod was found in {}. Installing it will most likely fail.", source),
+            vec![]
+          ),
+          Ok(contents) => (
+            format!("{} holds {} mod(s), {:.1} MB uncompressed:", source, contents.mods.len(), contents.size as f64 / (1024.0 * 1024.0)),
+            contents.mods.iter()
+              .map(|previewed| {
+                let replaces = previewed.replaces.as_ref().map_or_else(String::new, |path| format!(" - replaces {}", path.display()));
+
+                Text::new(format!(
+                  "{} ({}) {}, for Starsector {}{}",
+                  previewed.mod_info.name,
+                  previewed.mod_info.id,
+                  previewed.mod_info.version,
+                  previewed.mod_info.game_version,
+                  replaces
+                )).into()
+              })
+              .collect()
+          ),
+          Err(err) => (format!("Could not preview {}: {}", source, err), vec![])
+        };
+
+        Column::new()
+          .push(Text::new(heading))
+          .push(Row::new()
+            .push(Space::with_width(Length::Units(10)))
+            .push(Column::with_children(lines).spacing(2))
+          )
+          .push(Row::new()
+            .push(Button::new(&mut preview.install_state, Text::new("Install"))
+              .on_press(ModListMessage::PreviewInstall(preview_idx))
+              .style(style::button_only_hover::Button)
+              .padding(2)
+            )
+            .push(Button::new(&mut preview.dismiss_state, Text::new("Cancel"))
+              .on_press(ModListMessage::PreviewDismiss(preview_idx))
+              .style(style::button_only_hover::Button)
+              .padding(2)
+            )
+            .spacing(10)
+          )
+          .spacing(5)
+          .into()
+      })
+      .collect();
+    let conflict_lines = if self.bottom_pane == BottomPane::Conflicts {
+      self.conflict_lines()
+    } else {
+      vec![]
+    };
     let mut every_other = true;
     let content = Column::new()
       .push(Row::new()
@@ -533,6 +1143,8 @@ impl ModList {
           Some(ToolOptions::Default),
           ModListMessage::ToolsPressed
         ))
+        .push(Space::with_width(Length::Units(10)))
+        .push(self.profile_controls.view().map(ModListMessage::ProfilesMessage))
         .push(Space::with_width(Length::Fill))
         .push(Button::new(
           &mut self.launch_button_state,
@@ -569,52 +1181,52 @@ impl ModList {
             sorted_mods.sort_by(|left, right| {
               match cmp {
                 (ModEntryComp::Score, _) => right.search_score.cmp(&left.search_score),
-                (ModEntryComp::ID, false) => left.id.cmp(&right.id),
-                (ModEntryComp::Name, false) => left.name.cmp(&right.name),
-                (ModEntryComp::Author, false) => left.author.cmp(&right.author),
-                (ModEntryComp::Enabled, false) => left.enabled.cmp(&right.enabled),
-                (ModEntryComp::GameVersion, false) => left.game_version.cmp(&right.game_version),
+                (ModEntryComp::ID, false) => left.mod_info.id.cmp(&right.mod_info.id),
+                (ModEntryComp::Name, false) => left.mod_info.name.cmp(&right.mod_info.name),
+                (ModEntryComp::Author, false) => left.mod_info.author.cmp(&right.mod_info.author),
+                (ModEntryComp::Enabled, false) => left.mod_info.enabled.cmp(&right.mod_info.enabled),
+                (ModEntryComp::GameVersion, false) => left.mod_info.game_version.cmp(&right.mod_info.game_version),
                 (ModEntryComp::Version, false) => {
-                  if left.update_status.is_none() && right.update_status.is_none() {
-                    left.name.cmp(&right.name)
-                  } else if left.update_status.is_none() {
+                  if left.mod_info.update_status.is_none() && right.mod_info.update_status.is_none() {
+                    left.mod_info.name.cmp(&right.mod_info.name)
+                  } else if left.mod_info.update_status.is_none() {
                     std::cmp::Ordering::Greater
-                  } else if right.update_status.is_none() {
+                  } else if right.mod_info.update_status.is_none() {
                     std::cmp::Ordering::Less
                   } else {
-                    if left.update_status.cmp(&right.update_status) == std::cmp::Ordering::Equal {
-                      left.name.cmp(&right.name)
+                    if left.mod_info.update_status.cmp(&right.mod_info.update_status) == std::cmp::Ordering::Equal {
+                      left.mod_info.name.cmp(&right.mod_info.name)
                     } else {
-                      left.update_status.cmp(&right.update_status)
+                      left.mod_info.update_status.cmp(&right.mod_info.update_status)
                     }
                   }
 
                 },
                 (ModEntryComp::AutoUpdateSupport, true) => {
-                  left.remote_version.as_ref().and_then(|r| r.direct_download_url.as_ref()).is_some()
-                    .cmp(&right.remote_version.as_ref().and_then(|r| r.direct_download_url.as_ref()).is_some())
+                  left.mod_info.remote_version.as_ref().and_then(|r| r.direct_download_url.as_ref()).is_some()
+                    .cmp(&right.mod_info.remote_version.as_ref().and_then(|r| r.direct_download_url.as_ref()).is_some())
                 }
-                (ModEntryComp::ID, true) => right.id.cmp(&left.id),
-                (ModEntryComp::Name, true) => right.name.cmp(&left.name),
-                (ModEntryComp::Author, true) => right.author.cmp(&left.author),
-                (ModEntryComp::Enabled, true) => right.enabled.cmp(&left.enabled),
-                (ModEntryComp::GameVersion, true) => right.game_version.cmp(&left.game_version),
+                (ModEntryComp::ID, true) => right.mod_info.id.cmp(&left.mod_info.id),
+                (ModEntryComp::Name, true) => right.mod_info.name.cmp(&left.mod_info.name),
+                (ModEntryComp::Author, true) => right.mod_info.author.cmp(&left.mod_info.author),
+                (ModEntryComp::Enabled, true) => right.mod_info.enabled.cmp(&left.mod_info.enabled),
+                (ModEntryComp::GameVersion, true) => right.mod_info.game_version.cmp(&left.mod_info.game_version),
                 (ModEntryComp::Version, true) => {
-                  if right.update_status.is_none() && left.update_status.is_none() {
-                    left.name.cmp(&right.name)
-                  } else if right.update_status.is_none() {
+                  if right.mod_info.update_status.is_none() && left.mod_info.update_status.is_none() {
+                    left.mod_info.name.cmp(&right.mod_info.name)
+                  } else if right.mod_info.update_status.is_none() {
                     std::cmp::Ordering::Greater
-                  } else if left.update_status.is_none() {
+                  } else if left.mod_info.update_status.is_none() {
                     std::cmp::Ordering::Less
-                  } else if right.update_status.cmp(&left.update_status) == std::cmp::Ordering::Equal {
-                    left.name.cmp(&right.name)
+                  } else if right.mod_info.update_status.cmp(&left.mod_info.update_status) == std::cmp::Ordering::Equal {
+                    left.mod_info.name.cmp(&right.mod_info.name)
                   } else {
-                    right.update_status.cmp(&left.update_status)
+                    right.mod_info.update_status.cmp(&left.mod_info.update_status)
                   }
                 },
                 (ModEntryComp::AutoUpdateSupport, false) => {
-                  right.remote_version.as_ref().and_then(|r| r.direct_download_url.as_ref()).is_some()
-                    .cmp(&left.remote_version.as_ref().and_then(|r| r.direct_download_url.as_ref()).is_some())
+                  right.mod_info.remote_version.as_ref().and_then(|r| r.direct_download_url.as_ref()).is_some()
+                    .cmp(&left.mod_info.remote_version.as_ref().and_then(|r| r.direct_download_url.as_ref()).is_some())
                 }
               }
             });
@@ -631,7 +1243,7 @@ impl ModList {
               .filter(|entry| entry.display)
               .for_each(|entry| {
                 every_other = !every_other;
-                let id_clone = entry.id.clone();
+                let id_clone = entry.mod_info.id.clone();
                 views.push(entry.view(every_other,
                   name_portion as u16,
                   id_portion as u16,
@@ -660,6 +1272,37 @@ impl ModList {
           .push(Space::with_width(Length::Units(10)))
         )
       )
+      .push::<Element<ModListMessage>>(if previews.len() > 0 {
+        Column::new()
+          .push(Rule::horizontal(1).style(style::max_rule::Rule))
+          .push(Column::with_children(previews).spacing(10))
+          .spacing(5)
+          .padding(5)
+          .into()
+      } else {
+        Space::with_height(Length::Shrink).into()
+      })
+      .push::<Element<ModListMessage>>(if bundles.len() > 0 {
+        Column::new()
+          .push(Rule::horizontal(1).style(style::max_rule::Rule))
+          .push(Column::with_children(bundles).spacing(10))
+          .spacing(5)
+          .padding(5)
+          .into()
+      } else {
+        Space::with_height(Length::Shrink).into()
+      })
+      .push::<Element<ModListMessage>>(if install_queue.len() > 0 {
+        Column::new()
+          .push(Rule::horizontal(1).style(style::max_rule::Rule))
+          .push(Text::new("Installing:"))
+          .push(Column::with_children(install_queue).spacing(2))
+          .spacing(5)
+          .padding(5)
+          .into()
+      } else {
+        Space::with_height(Length::Shrink).into()
+      })
       .push(Rule::horizontal(1).style(style::max_rule::Rule))
       .push(Space::with_height(Length::Units(5)))
       .push(Row::new()
@@ -688,89 +1331,114 @@ impl ModList {
       .push(Rule::horizontal(1).style(style::max_rule::Rule))
       .push(Space::with_height(Length::Units(5)))
       .push(
-        Container::new(self.mod_description.view().map(|message| {
-          ModListMessage::ModDescriptionMessage(message)
-        }))
-        .height(Length::FillPortion(2))
-        .width(Length::Fill)
-      );
-
-    Column::new()
-      .push(content)
+        Container::new::<Element<ModListMessage>>(match self.bottom_pane {
+          BottomPane::Description => self.mod_description.view().map(|message| {
+            ModListMessage::ModDescriptionMessage(message)
+          }),
+          pane => Column::new()
+            .push(Row::new()
+              .push(Text::new(if pane == BottomPane::Conflicts {
+                "File and row conflicts between enabled mods"
+              } else {
+                "Recently removed mods - restore a mod to move it back into your mods folder"
+              }))
+              .push(Space::with_width(Length::Fill))
+              .push(Button::new(&mut self.bottom_pane_close_state, Text::new("Close"))
+                .on_press(ModListMessage::CloseBottomPane)
+                .style(style::button_only_hover::Button)
+                .padding(5)
+              )
+              .align_items(Align::Center)
+            )
+            .push::<Element<ModListMessage>>(if pane == BottomPane::Conflicts {
+              Scrollable::new(&mut self.conflicts_scroll)
+                .push(Column::with_children(conflict_lines.into_iter().map(|(line, kind)| {
+                  let text = Text::new(line);
+
+                  match kind {
+                    Some(ConflictKind::Replaced) => text.color(iced::Color::from_rgb8(0xFF, 0xA0, 0x00)),
+                    Some(ConflictKind::Merged) => text.color([0.7, 0.7, 0.7]),
+                    None => text
+                  }.into()
+                }).collect()))
+                .height(Length::Fill)
+                .into()
+            } else {
+              self.recently_removed.view().map(ModListMessage::RecentlyRemovedMessage)
+            })
+            .padding(5)
+            .into()
+        })
+        .height(Length::FillPortion(2))
+        .width(Length::Fill)
+      );
+
+    Column::new()
+      .push(content)
       .padding(5)
       .height(Length::Fill)
       .into()
   }
 
   pub fn subscription(&self) -> Subscription<ModListMessage> {
+    // Holding Ctrl (or Cmd) while clicking mods selects several at once
+    let modifiers = iced_native::subscription::events_with(|event, _| match event {
+      iced_native::Event::Keyboard(iced_native::keyboard::Event::ModifiersChanged(modifiers)) => {
+        Some(ModListMessage::ModifiersChanged(modifiers.control || modifiers.logo))
+      },
+      _ => None
+    });
+
     if self.installs.len() > 0 {
-      return Subscription::batch(self.installs.iter().map(|i| i.clone().install())).map(|message| match message {
-        installer::Progress::Query(name, id, new_path, old_path) => ModListMessage::DuplicateMod(name, id, new_path, old_path),
-        installer::Progress::Completed(id, completed, failed) => ModListMessage::InstallationComplete(id, completed, failed),
-        installer::Progress::Finished => ModListMessage::SingleInstallComplete
-      })
+      return Subscription::batch(vec![
+        Subscription::batch(self.installs.iter().map(|i| i.clone().install())).map(|message| match message {
+          installer::Progress::Started(id, labels) => ModListMessage::InstallationStarted(id, labels),
+          installer::Progress::Status(id, job, status) => ModListMessage::InstallJobStatus(id, job, status),
+          installer::Progress::Query(name, id, new_path, old_path) => ModListMessage::DuplicateMod(name, id, new_path, old_path),
+          installer::Progress::Bundle(source, found) => ModListMessage::BundleFound(source, found),
+          installer::Progress::Completed(id, completed, failed) => ModListMessage::InstallationComplete(id, completed, failed),
+          installer::Progress::Finished => ModListMessage::SingleInstallComplete
+        }),
+        modifiers
+      ])
     }
 
-    Subscription::none()
+    modifiers
+  }
+
+  fn load_preview(path: PathBuf) -> Command<ModListMessage> {
+    let source = path.clone();
+
+    Command::perform(
+      async move { starsector_mod_manager::installer::preview(source).await.map_err(|err| err.to_string()) },
+      move |res| ModListMessage::PreviewLoaded(path.clone(), res)
+    )
   }
 
   #[must_use]
   fn parse_mod_folder(&mut self) -> Vec<Command<ModListMessage>>{
     self.mods.clear();
+    self.selected.clear();
 
     if let Some(root_dir) = &self.root_dir {
-      let mod_dir = root_dir.join("mods");
-      let enabled_mods_filename = mod_dir.join("enabled_mods.json");
+      if let Ok(mut repository) = ModRepository::scan(root_dir.join("mods")) {
+        repository.apply_update_holds(&self.update_holds);
+        let versions = repository.version_checks();
 
-      let enabled_mods = if !enabled_mods_filename.exists() {
-        vec![]
-      } else {
-        if_chain! {
-          if let Ok(enabled_mods_text) = std::fs::read_to_string(enabled_mods_filename);
-          if let Ok(EnabledMods { enabled_mods }) = serde_json::from_str::<EnabledMods>(&enabled_mods_text);
-          then {
-            enabled_mods
-          } else {
-            return vec![Command::perform(async {}, ModListMessage::ParseModListError)]
-          }
-        }
-      };
+        self.mods.extend(repository.mods.into_iter().map(|(id, mod_info)| (id, ModEntry::from(mod_info))));
+        self.refresh_mod_state();
 
-      if let Ok(dir_iter) = std::fs::read_dir(mod_dir) {
-        let enabled_mods_iter = enabled_mods.iter();
+        // Every mod is scanned, not just enabled ones, so toggling a mod doesn't require walking its folder again
+        let entries: Vec<mods::ModEntry> = self.mods.values().map(|entry| entry.mod_info.clone()).collect();
+        let scan_files = Command::perform(async move {
+          tokio::task::spawn_blocking(move || ModFiles::scan_all(entries)).await.unwrap_or_default()
+        }, ModListMessage::ModFilesScanned);
+        // Anything that changes the mods folder may also have moved a mod into the trash
+        let load_trash = self.recently_removed.update(RecentlyRemovedMessage::Load).map(ModListMessage::RecentlyRemovedMessage);
 
-        let (mods, versions): (Vec<(String, ModEntry)>, Vec<Option<ModVersionMeta>>) = dir_iter
-          .filter_map(|entry| entry.ok())
-          .filter(|entry| {
-            if let Ok(file_type) = entry.file_type() {
-              file_type.is_dir()
-            } else {
-              false
-            }
-          })
-          .filter_map(|entry| {
-            if let Ok(mut mod_info) = ModEntry::from_file(&entry.path()) {
-              mod_info.enabled = enabled_mods_iter.clone().find(|id| mod_info.id.clone().eq(*id)).is_some();
-              Some((
-                (
-                  mod_info.id.clone(),
-                  mod_info.clone()
-                ),
-                mod_info.version_checker.clone()
-              ))
-            } else {
-              dbg!(entry.path());
-              None
-            }
-          })
-          .unzip();
+        let check_versions = Command::perform(version_cache::load(), move |cache| ModListMessage::VersionCacheLoaded(versions.clone(), cache));
 
-        self.mods.extend(mods);
-
-        versions.iter()
-          .filter_map(|v| v.as_ref())
-          .map(|v| Command::perform(util::get_master_version(v.clone()), ModListMessage::MasterVersionReceived))
-          .collect()
+        vec![check_versions, scan_files, load_trash]
       } else {
         // debug_println!("Fatal. Could not parse mods folder. Alert developer");
         vec![Command::perform(async {}, ModListMessage::ParseModListError)]
@@ -780,6 +1448,400 @@ impl ModList {
     }
   }
 
+  /**
+   * Without a modifier held, highlighting a mod clears the rest of the selection. With one held, clicking a selected mod deselects it.
+   * The description pane always shows the last mod added to the selection.
+   */
+  fn highlight(&mut self, id: String) {
+    if self.multi_select && self.selected.remove(&id) {
+      if let Some(entry) = self.mods.get_mut(&id) {
+        entry.update(ModEntryMessage::EntryCleared);
+      }
+
+      return;
+    }
+
+    if !self.multi_select {
+      for other in self.selected.drain().collect::<Vec<String>>() {
+        if let Some(entry) = self.mods.get_mut(&other) {
+          entry.update(ModEntryMessage::EntryCleared);
+        }
+      }
+    }
+
+    if let Some(entry) = self.mods.get_mut(&id) {
+      entry.update(ModEntryMessage::EntryHighlighted);
+      self.mod_description.update(ModDescriptionMessage::ModChanged(entry.mod_info.clone(), entry.dependencies.clone()));
+    }
+    self.selected.insert(id.clone());
+    self.currently_highlighted = Some(id);
+  }
+
+  /**
+   * Asks once to update every given mod with a newer version available, then queues those that can be downloaded directly
+   * as a single installation. Mods that have to be downloaded by hand are listed in the confirmation and the final summary.
+   */
+  fn update_mods(&mut self, ids: Vec<String>) -> Command<ModListMessage> {
+    let root_dir = if let Some(root_dir) = self.root_dir.clone() {
+      root_dir
+    } else {
+      util::error("No install directory set. Please set the Starsector install directory in Settings.");
+      return Command::none();
+    };
+
+    let mut outdated: Vec<&ModEntry> = ids.iter()
+      .filter_map(|id| self.mods.get(id))
+      .filter(|entry| entry.mod_info.any_update_available())
+      .collect();
+    outdated.sort_by(|left, right| left.mod_info.name.cmp(&right.mod_info.name));
+    if outdated.len() == 0 {
+      util::notif("No updates are available for these mods.");
+      return Command::none();
+    }
+
+    // Bundled Version Checker files have no download of their own, so a mod with only bundled updates is updated by hand
+    let (automatic, manual): (Vec<&ModEntry>, Vec<&ModEntry>) = outdated.into_iter()
+      .partition(|entry| {
+        entry.mod_info.update_status.as_ref().map_or(false, UpdateStatus::is_update_available)
+          && entry.mod_info.remote_version.as_ref().map_or(false, |remote| remote.direct_download_url.is_some())
+      });
+    let manual: Vec<String> = manual.into_iter()
+      .map(|entry| {
+        let bundled = bundled_updates(&entry.mod_info);
+
+        if entry.mod_info.update_status.as_ref().map_or(false, UpdateStatus::is_update_available) || bundled.len() == 0 {
+          entry.mod_info.name.clone()
+        } else {
+          format!("{} ({})", entry.mod_info.name, bundled.join(", "))
+        }
+      })
+      .collect();
+    if automatic.len() == 0 {
+      util::notif(format!("The following mods have updates available, but must be downloaded and updated manually:\n{}", manual.join(", ")));
+      return Command::none();
+    }
+
+    let mut message = format!(
+      "Do you want to automatically download and update the following mods?\n{}",
+      automatic.iter()
+        .map(|entry| format!(
+          "{}: {} -> {}",
+          entry.mod_info.name,
+          entry.mod_info.version,
+          entry.mod_info.remote_version.as_ref().map_or_else(String::new, |remote| remote.version.to_string())
+        ))
+        .collect::<Vec<String>>()
+        .join("\n")
+    );
+    if manual.len() > 0 {
+      message.push_str(&format!("\nThe following must be downloaded and updated manually: {}", manual.join(", ")));
+    }
+    message.push_str("\nWARNING: Save compatibility is not guaranteed when updating a mod. Your save may no longer load if you apply these updates.");
+    if !util::query(message) {
+      return Command::none();
+    }
+
+    let updates = automatic.into_iter()
+      .filter_map(|entry| {
+        let remote = entry.mod_info.remote_version.as_ref()?;
+
+        Some((
+          remote.direct_download_url.clone()?,
+          remote.version.to_string(),
+          entry.mod_info.path.clone(),
+          ArchiveChecksum::from(remote)
+        ))
+      })
+      .collect();
+    self.installs.push(Installation::new(
+      self.installation_id,
+      installer::Payload::Downloads(updates),
+      root_dir.join("mods"),
+      Vec::new(),
+      self.install_limit.clone()
+    ).download_options(self.download_options));
+    self.manual_updates.insert(self.installation_id, manual);
+
+    self.installation_id += 1;
+
+    Command::none()
+  }
+
+  /**
+   * Removes the given mods from enabled_mods.json straight away, then moves their folders into the trash, or somewhere else if the
+   * user would rather keep a copy of their own.
+   */
+  fn uninstall_mods(&mut self, ids: Vec<String>) -> Command<ModListMessage> {
+    let root_dir = if let Some(root_dir) = self.root_dir.clone() {
+      root_dir
+    } else {
+      util::error("No install directory set. Please set the Starsector install directory in Settings.");
+      return Command::none();
+    };
+
+    let entries: Vec<(String, String, PathBuf)> = ids.iter()
+      .filter_map(|id| self.mods.get(id))
+      .map(|entry| (entry.mod_info.id.clone(), entry.mod_info.name.clone(), entry.mod_info.path.clone()))
+      .collect();
+    if entries.len() == 0 {
+      util::notif("No mods selected. Click a mod to select it, or hold Ctrl while clicking to select several.");
+      return Command::none();
+    }
+
+    let dependents: Vec<String> = self.mods.values()
+      .filter(|entry| entry.mod_info.enabled && !ids.contains(&entry.mod_info.id) && ids.iter().any(|id| entry.mod_info.depends_on(id)))
+      .map(|entry| entry.mod_info.name.clone())
+      .collect();
+    let developing: Vec<String> = entries.iter()
+      .filter(|(_, _, path)| self.git_warn && path.join(".git").exists())
+      .map(|(_, name, _)| name.clone())
+      .collect();
+
+    let mut message = format!(
+      "Do you want to uninstall the following mods?\n{}",
+      entries.iter().map(|(_, name, _)| name.clone()).collect::<Vec<String>>().join(", ")
+    );
+    if dependents.len() > 0 {
+      message.push_str(&format!("\nWarning: the following enabled mods depend on them, and will likely crash the game: {}", dependents.join(", ")));
+    }
+    if developing.len() > 0 {
+      message.push_str(&format!("\nWarning: the following mods include a .git folder, are you developing them? {}", developing.join(", ")));
+    }
+    if !util::query(message) {
+      return Command::none();
+    }
+
+    let move_to = if util::query("Do you want to keep a copy of the uninstalled mods by moving them to another folder?\nClicking no will move them to Recently Removed, from where they can be restored until they are purged.") {
+      let start_path = self.last_browsed.clone().unwrap_or_else(|| root_dir.clone());
+      match util::select_folder_dialog("Select folder to move mods to:", &start_path.to_string_lossy()) {
+        Some(move_to) => Some(move_to),
+        None => return Command::none()
+      }
+    } else {
+      None
+    };
+
+    for (id, ..) in &entries {
+      if let Some(entry) = self.mods.get_mut(id) {
+        entry.update(ModEntryMessage::ToggleEnabled(false));
+      }
+    }
+    let enabled_mods = EnabledMods {
+      enabled_mods: self.enabled_ids(),
+    };
+
+    Command::batch(vec![
+      Command::perform(enabled_mods.save(root_dir.join("mods").join("enabled_mods.json")), ModListMessage::EnabledModsSaved),
+      Command::perform(async move {
+        let mut results = vec![];
+        for (_, name, path) in entries {
+          results.push((name, uninstall(path, move_to.clone()).await.map_err(|err| err.to_string())));
+        }
+
+        results
+      }, ModListMessage::UninstallComplete)
+    ])
+  }
+
+  /**
+   * Enabling a mod offers to enable its disabled dependencies, and disabling one asks for confirmation if enabled mods depend on it.
+   * Total conversions can't be enabled alongside other content mods, so enabling one side asks to disable the other.
+   */
+  fn toggle_enabled(&mut self, id: String, enabled: bool) -> Command<ModListMessage> {
+    let name = if let Some(entry) = self.mods.get(&id) {
+      entry.mod_info.name.clone()
+    } else {
+      return Command::none();
+    };
+    let mut changed = vec![id.clone()];
+    let mut excluded: Vec<String> = vec![];
+
+    if enabled {
+      let mut disabled: Vec<String> = vec![];
+      let mut unsatisfiable: Vec<String> = vec![];
+      let mut queue = vec![id.clone()];
+      while let Some(current) = queue.pop() {
+        if let Some(entry) = self.mods.get(&current) {
+          let mods = &self.mods;
+          for (dependency, status) in entry.mod_info.dependency_statuses(|id| mods.get(id).map(|entry| &entry.mod_info)) {
+            match status {
+              DependencyStatus::Satisfied => {},
+              DependencyStatus::Disabled => if !changed.contains(&dependency.id) && !disabled.contains(&dependency.id) {
+                disabled.push(dependency.id.clone());
+                queue.push(dependency.id);
+              },
+              _ => unsatisfiable.push(format!("{} requires {} - {}", entry.mod_info.name, dependency, status))
+            }
+          }
+        }
+      }
+
+      if disabled.len() > 0 {
+        let names: Vec<String> = disabled.iter()
+          .filter_map(|id| self.mods.get(id))
+          .map(|entry| entry.mod_info.name.clone())
+          .collect();
+        if util::query(format!("{} depends on the following mods, which are disabled:\n{}\nDo you want to enable them as well?", name, names.join(", "))) {
+          changed.extend(disabled);
+        }
+      }
+      if unsatisfiable.len() > 0 {
+        util::notif(format!("Some dependencies can't be satisfied by the installed mods:\n{}", unsatisfiable.join("\n")));
+      }
+
+      let conflicts: Vec<&ModEntry> = self.mods.values()
+        .filter(|other| other.mod_info.enabled && !changed.contains(&other.mod_info.id))
+        .filter(|other| changed.iter()
+          .filter_map(|id| self.mods.get(id))
+          .any(|entry| entry.mod_info.excludes(&other.mod_info))
+        )
+        .collect();
+      if conflicts.len() > 0 {
+        let names: Vec<String> = conflicts.iter().map(|entry| entry.mod_info.name.clone()).collect();
+        let total_conversion = self.mods.get(&id).map_or(false, |entry| entry.mod_info.total_conversion);
+        if util::query(format!(
+          "{}\n{}\nDo you want to disable {}?\nClicking no will cancel enabling {}.",
+          if total_conversion {
+            format!("{} is a total conversion, and can't be used alongside other content mods. The following mods are enabled:", name)
+          } else {
+            format!("The following enabled mods are total conversions, and can't be used alongside {}:", name)
+          },
+          names.join(", "),
+          if names.len() > 1 { "them" } else { "it" },
+          name
+        )) {
+          excluded = conflicts.iter().map(|entry| entry.mod_info.id.clone()).collect();
+        } else {
+          return Command::none();
+        }
+      }
+    } else {
+      let dependents: Vec<String> = self.mods.values()
+        .filter(|entry| entry.mod_info.enabled && entry.mod_info.depends_on(&id))
+        .map(|entry| entry.mod_info.name.clone())
+        .collect();
+      if dependents.len() > 0 && !util::query(format!(
+        "The following enabled mods depend on {}:\n{}\nThey will likely crash the game without it. Disable it anyway?",
+        name,
+        dependents.join(", ")
+      )) {
+        return Command::none();
+      }
+    }
+
+    for id in &changed {
+      if let Some(entry) = self.mods.get_mut(id) {
+        entry.update(ModEntryMessage::ToggleEnabled(enabled));
+      }
+    }
+    for id in &excluded {
+      if let Some(entry) = self.mods.get_mut(id) {
+        entry.update(ModEntryMessage::ToggleEnabled(false));
+      }
+    }
+    self.refresh_mod_state();
+
+    if let Some(path) = &self.root_dir {
+      let enabled_mods = EnabledMods {
+        enabled_mods: self.enabled_ids(),
+      };
+      Command::perform(enabled_mods.save(path.join("mods").join("enabled_mods.json")), ModListMessage::EnabledModsSaved)
+    } else {
+      Command::none()
+    }
+  }
+
+  /**
+   * Must be called whenever mods are enabled, disabled or rescanned, as a mod's dependency status and file conflicts depend on every
+   * other mod.
+   */
+  fn refresh_mod_state(&mut self) {
+    let mods = &self.mods;
+    let statuses: Vec<(String, Vec<(Dependency, DependencyStatus)>)> = mods.iter()
+      .map(|(id, entry)| (id.clone(), entry.mod_info.dependency_statuses(|id| mods.get(id).map(|entry| &entry.mod_info))))
+      .collect();
+
+    for (id, dependencies) in statuses {
+      if let Some(entry) = self.mods.get_mut(&id) {
+        entry.dependencies = dependencies;
+      }
+    }
+
+    let mods = &self.mods;
+    let conflicts = ConflictReport::new(self.mod_files.values().filter(|files| mods.get(&files.id).map_or(false, |entry| entry.mod_info.enabled)));
+    for (id, entry) in self.mods.iter_mut() {
+      entry.conflict = conflicts.worst_conflict(id);
+    }
+    self.conflicts = conflicts;
+
+    let highlighted = self.currently_highlighted.as_ref()
+      .and_then(|id| self.mods.get(id))
+      .map(|entry| (entry.mod_info.clone(), entry.dependencies.clone()));
+    if let Some((mod_info, dependencies)) = highlighted {
+      self.mod_description.update(ModDescriptionMessage::ModChanged(mod_info, dependencies));
+    } else if self.currently_highlighted.is_some() {
+      // The highlighted mod has been uninstalled
+      self.currently_highlighted = None;
+      self.mod_description.mod_entry = None;
+    }
+  }
+
+  /**
+   * The conflict report as display lines - a heading per pair of mods followed by the files they share, then every spreadsheet row
+   * defined by more than one mod. Overridden rows are always shown as replaced, as only the winning mod's row is used.
+   */
+  fn conflict_lines(&self) -> Vec<(String, Option<ConflictKind>)> {
+    if self.conflicts.is_empty() {
+      return vec![(format!("No enabled mods share any files."), None)];
+    }
+
+    let name = |id: &String| self.mods.get(id).map_or_else(|| id.clone(), |entry| entry.mod_info.name.clone());
+    let mut lines: Vec<(String, Option<ConflictKind>)> = self.conflicts.pairs.iter()
+      .flat_map(|((left, right), conflicts)| {
+        std::iter::once((format!("{} and {}:", name(left), name(right)), None))
+          .chain(conflicts.iter().map(|conflict| (format!("    [{}] {}", conflict.kind, conflict.path), Some(conflict.kind))))
+      })
+      .collect();
+
+    if self.conflicts.rows.len() > 0 {
+      lines.push((String::new(), None));
+      lines.push((format!("Spreadsheet rows defined by more than one mod (mods load in order of ID, the last one wins):"), None));
+
+      let mut file = None;
+      for row in &self.conflicts.rows {
+        if file != Some(&row.file) {
+          lines.push((format!("{}:", row.file), None));
+          file = Some(&row.file);
+        }
+        lines.push((
+          format!("    `{}` - {} - {} wins", row.id, row.mods.iter().map(|id| name(id)).collect::<Vec<String>>().join(", "), name(row.winner())),
+          Some(ConflictKind::Replaced)
+        ));
+      }
+    }
+
+    lines
+  }
+
+  fn enabled_ids(&self) -> Vec<String> {
+    self.mods.iter()
+      .filter_map(|(id, entry)| entry.mod_info.enabled.then(|| id.clone()))
+      .collect()
+  }
+
+  /**
+   * The active profile's name, marked as modified if the enabled mods no longer match it.
+   */
+  pub fn get_active_profile(&self) -> Option<String> {
+    let profiles = &self.profile_controls.profiles;
+
+    profiles.active.clone().map(|name| if profiles.is_modified(&self.enabled_ids()) {
+      format!("{} (modified)", name)
+    } else {
+      name
+    })
+  }
+
   pub fn get_game_version(&self) -> Option<String> {
     match &self.starsector_version {
       (None, None, None, None) => None,
@@ -801,15 +1863,18 @@ pub enum InstallOptions {
   FromMultipleArchive,
   FromSingleArchive,
   FromFolder,
+  FromUrl,
   FromDownload(String, String, PathBuf),
+  FromCache(PathBuf),
   Default
 }
 
 impl InstallOptions {
-  const SHOW: [InstallOptions; 3] = [
+  const SHOW: [InstallOptions; 4] = [
     InstallOptions::FromMultipleArchive,
     InstallOptions::FromSingleArchive,
-    InstallOptions::FromFolder
+    InstallOptions::FromFolder,
+    InstallOptions::FromUrl
   ];
 }
 
@@ -823,7 +1888,9 @@ impl std::fmt::Display for InstallOptions {
         InstallOptions::FromMultipleArchive => "From Multiple Archives",
         InstallOptions::FromSingleArchive => "From Single Archive",
         InstallOptions::FromDownload(_, _, _) => "From Download",
-        InstallOptions::FromFolder => "From Folder"
+        InstallOptions::FromCache(_) => "From Archive Cache",
+        InstallOptions::FromFolder => "From Folder",
+        InstallOptions::FromUrl => "From URL"
       }
     )
   }
@@ -840,12 +1907,18 @@ pub enum ToolOptions {
   FilterError,
   FilterUnsupported,
   FilterDiscrepancy,
+  FilterUtility,
   FilterNone,
+  ShowConflicts,
+  ShowRecentlyRemoved,
+  UninstallSelected,
+  UpdateAll,
+  UpdateSelected,
   Refresh,
 }
 
 impl ToolOptions {
-  const SHOW: [ToolOptions; 10] = [
+  const SHOW: [ToolOptions; 16] = [
     ToolOptions::EnableAll,
     ToolOptions::DisableAll,
     ToolOptions::FilterEnabled,
@@ -854,7 +1927,13 @@ impl ToolOptions {
     ToolOptions::FilterError,
     ToolOptions::FilterUnsupported,
     ToolOptions::FilterDiscrepancy,
+    ToolOptions::FilterUtility,
     ToolOptions::FilterNone,
+    ToolOptions::ShowConflicts,
+    ToolOptions::ShowRecentlyRemoved,
+    ToolOptions::UninstallSelected,
+    ToolOptions::UpdateAll,
+    ToolOptions::UpdateSelected,
     ToolOptions::Refresh,
   ];
 }
@@ -874,96 +1953,48 @@ impl std::fmt::Display for ToolOptions {
         ToolOptions::FilterError => "Show Version Check Failed",
         ToolOptions::FilterUnsupported => "Show Version Check Unsupported",
         ToolOptions::FilterDiscrepancy => "Show Version Discrepancy",
+        ToolOptions::FilterUtility => "Show Utility Mods",
         ToolOptions::FilterNone => "Show All",
+        ToolOptions::ShowConflicts => "Show File & Row Conflicts",
+        ToolOptions::ShowRecentlyRemoved => "Show Recently Removed",
+        ToolOptions::UninstallSelected => "Uninstall Selected",
+        ToolOptions::UpdateAll => "Update All",
+        ToolOptions::UpdateSelected => "Update Selected",
         ToolOptions::Refresh => "Refresh Mod List",
       }
     )
   }
 }
 
-#[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord)]
-pub enum UpdateStatus {
-  Error,
-  Major(Version),
-  Minor(Version),
-  Patch(Version),
-  UpToDate,
-  Discrepancy(Version),
-}
-
-impl Display for UpdateStatus {
-  fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::result::Result<(), std::fmt::Error> {
-    match self {
-      UpdateStatus::Major(_) => write!(f, "Major"),
-      UpdateStatus::Minor(_) => write!(f, "Minor"),
-      UpdateStatus::Patch(_) => write!(f, "Patch"),
-      UpdateStatus::UpToDate => write!(f, "Up to date"),
-      UpdateStatus::Error => write!(f, "Error"),
-      UpdateStatus::Discrepancy(_) => write!(f, "Discrepancy"),
-    }
-  }
+/**
+ * The bundled Version Checker files of a mod that have updates available, described as `name: old -> new`.
+ */
+fn bundled_updates(mod_info: &mods::ModEntry) -> Vec<String> {
+  mod_info.bundled_versions.iter()
+    .filter(|bundled| bundled.update_status.as_ref().map_or(false, UpdateStatus::is_update_available))
+    .map(|bundled| format!(
+      "{}: {} -> {}",
+      bundled.name,
+      bundled.local.version,
+      bundled.remote.as_ref().map_or_else(String::new, |remote| remote.version.to_string())
+    ))
+    .collect()
 }
 
 pub struct UpdateStatusTTPatch(pub UpdateStatus);
 
-#[derive(Debug, Clone, Deserialize)]
-#[serde(untagged)]
-pub enum VersionUnion {
-  String(String),
-  Object(Version)
-}
+pub struct UpdateStatusContainerPatch(pub UpdateStatus);
 
-impl Display for VersionUnion {
-  fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::result::Result<(), std::fmt::Error> {
-    let output: String = match self {
-      VersionUnion::String(s) => s.to_string(),
-      VersionUnion::Object(o) => o.to_string()
-    };
-    write!(f, "{}", output)
-  }
-}
-
-impl From<VersionUnion> for String {
-  fn from(version_union: VersionUnion) -> Self {
-    version_union.to_string()
-  }
-}
-
-#[derive(Debug, Clone, Deserialize)]
+#[derive(Debug, Clone)]
 pub struct ModEntry {
-  pub id: String,
-  pub name: String,
-  #[serde(default)]
-  author: String,
-  pub version: VersionUnion,
-  description: String,
-  #[serde(alias = "gameVersion")]
-  game_version: String,
-  #[serde(skip)]
-  parsed_game_version: (Option<String>, Option<String>, Option<String>, Option<String>),
-  #[serde(skip)]
-  enabled: bool,
-  #[serde(skip)]
+  pub mod_info: mods::ModEntry,
   highlighted: bool,
-  #[serde(skip)]
-  pub version_checker: Option<ModVersionMeta>,
-  #[serde(skip)]
-  pub remote_version: Option<ModVersionMeta>,
-  #[serde(skip)]
-  update_status: Option<UpdateStatus>,
-  #[serde(skip)]
-  pub path: PathBuf,
-  #[serde(skip)]
-  #[serde(default = "button::State::new")]
   button_state: button::State,
-  #[serde(skip)]
-  #[serde(default = "button::State::new")]
   auto_update_button_state: button::State,
-  #[serde(skip)]
-  #[serde(default = "ModEntry::def_true")]
   display: bool,
-  #[serde(skip)]
   search_score: Option<isize>,
+  dependencies: Vec<(Dependency, DependencyStatus)>,
+  conflict: Option<ConflictKind>,
 }
 
 #[derive(Debug, Clone, PartialEq, Eq)]
@@ -986,56 +2017,26 @@ pub enum ModEntryMessage {
   AutoUpdate
 }
 
-pub enum ModEntryError {
-  ParseError,
-  FileError
-}
-
-impl ModEntry {
-  // const ICONS: iced::Font = iced::Font::External {
-  //   name: "Icons",
-  //   bytes: std::include_bytes!("../../assets/icons.ttf")
-  // };
-
-  pub fn from_file(path: &PathBuf) -> Result<ModEntry, ModEntryError> {
-    if let Ok(mod_info_file) = std::fs::read_to_string(path.join("mod_info.json")) {
-      if_chain! {
-        let mut stripped = String::new();
-        if strip_comments(mod_info_file.as_bytes()).read_to_string(&mut stripped).is_ok();
-        if let Ok(mut mod_info) = json5::from_str::<ModEntry>(&stripped);
-        then {
-          mod_info.version_checker = if_chain! {
-            if let Ok(version_loc_file) = File::open(path.join("data").join("config").join("version").join("version_files.csv"));
-            let lines = BufReader::new(version_loc_file).lines();
-            if let Some(Ok(version_filename)) = lines.skip(1).next();
-            if let Ok(version_data) = std::fs::read_to_string(path.join(version_filename));
-            let mut no_comments = String::new();
-            if strip_comments(version_data.as_bytes()).read_to_string(&mut no_comments).is_ok();
-            if let Ok(normalized) = handwritten_json::normalize(&no_comments);
-            if let Ok(mut version) = json5::from_str::<ModVersionMeta>(&normalized);
-            then {
-              version.id = mod_info.id.clone();
-              Some(version)
-            } else {
-              None
-            }
-          };
-          mod_info.path = path.clone();
-          mod_info.parsed_game_version = parse_game_version(&mod_info.game_version);
-          Ok(mod_info)
-        } else {
-          Err(ModEntryError::ParseError)
-        }
-      }
-    } else {
-      Err(ModEntryError::FileError)
+impl From<mods::ModEntry> for ModEntry {
+  fn from(mod_info: mods::ModEntry) -> Self {
+    ModEntry {
+      mod_info,
+      highlighted: false,
+      button_state: button::State::new(),
+      auto_update_button_state: button::State::new(),
+      display: true,
+      search_score: None,
+      dependencies: vec![],
+      conflict: None,
     }
   }
+}
 
+impl ModEntry {
   pub fn update(&mut self, message: ModEntryMessage) -> Command<ModEntryMessage> {
     match message {
       ModEntryMessage::ToggleEnabled(enabled) => {
-        self.enabled = enabled;
+        self.mod_info.enabled = enabled;
 
         Command::none()
       },
@@ -1066,7 +2067,16 @@ impl ModEntry {
     game_version_portion: u16,
     starsector_version: (Option<std::string::String>, Option<std::string::String>, Option<std::string::String>, Option<std::string::String>)
   ) -> Element<ModEntryMessage> {
-    let auto_update_supported = self.remote_version.as_ref().and_then(|remote| remote.direct_download_url.as_ref()).is_some();
+    let auto_update_supported = self.mod_info.remote_version.as_ref().and_then(|remote| remote.direct_download_url.as_ref()).is_some();
+    let enabled = self.mod_info.enabled;
+    let dependency_issues: Vec<String> = self.dependencies.iter()
+      .filter(|(_, status)| match status {
+        DependencyStatus::Satisfied => false,
+        DependencyStatus::Disabled => enabled,
+        _ => true
+      })
+      .map(|(dependency, status)| format!("{} - {}", dependency, status))
+      .collect();
 
     let mut auto_update_button = Button::new(
       &mut self.auto_update_button_state,
@@ -1097,7 +2107,7 @@ impl ModEntry {
     auto_update_button = if auto_update_supported {
       let button = auto_update_button.style(style::button_highlight_and_hover_green::Button);
 
-      if self.update_status != Some(UpdateStatus::UpToDate) {
+      if !matches!(self.mod_info.update_status, Some(UpdateStatus::UpToDate) | Some(UpdateStatus::Held(_))) {
         button.on_press(ModEntryMessage::AutoUpdate)
       } else {
         button
@@ -1109,7 +2119,7 @@ impl ModEntry {
     let row = Container::new(Row::new()
       .push(
         Container::new(
-          Checkbox::new(self.enabled, "", move |toggled| {
+          Checkbox::new(self.mod_info.enabled, "", move |toggled| {
             ModEntryMessage::ToggleEnabled(toggled)
           })
         )
@@ -1125,58 +2135,97 @@ impl ModEntry {
             .push(Container::new(Row::new()
               .push(Rule::vertical(0).style(style::max_rule::Rule))
               .push(Space::with_width(Length::Units(5)))
-              .push(Text::new(self.name.clone()).width(Length::Fill))
+              .push(Text::new(self.mod_info.name.clone()).width(Length::Fill))
+              .push::<Element<ModEntryMessage>>(if self.mod_info.total_conversion {
+                Text::new("TOTAL CONVERSION").size(14).color([0.7, 0.7, 0.7]).into()
+              } else if self.mod_info.utility {
+                Text::new("UTILITY").size(14).color([0.7, 0.7, 0.7]).into()
+              } else {
+                Space::with_width(Length::Shrink).into()
+              })
+              .push(Space::with_width(Length::Units(5)))
+              .push::<Element<ModEntryMessage>>(match self.conflict {
+                Some(ConflictKind::Replaced) => Tooltip::new(
+                  Text::new("FILE CONFLICTS").size(14).color(iced::Color::from_rgb8(0xFF, 0xA0, 0x00)),
+                  "Overwrites or is overwritten by files or spreadsheet rows from other enabled mods.\nSee Tools > Show File & Row Conflicts.",
+                  tooltip::Position::FollowCursor
+                ).style(style::update::major::Tooltip).into(),
+                Some(ConflictKind::Merged) => Tooltip::new(
+                  Text::new("SHARED FILES").size(14).color([0.7, 0.7, 0.7]),
+                  "Shares data files with other enabled mods, which the game merges.\nSee Tools > Show File & Row Conflicts.",
+                  tooltip::Position::FollowCursor
+                ).style(style::update::up_to_date::Tooltip).into(),
+                None => Space::with_width(Length::Shrink).into()
+              })
+              .push(Space::with_width(Length::Units(5)))
+              .push::<Element<ModEntryMessage>>(if dependency_issues.len() > 0 {
+                Tooltip::new(
+                  Text::new('\u{f33b}').font(style::ICONS).color(iced::Color::from_rgb8(0xB0, 0x00, 0x20)),
+                  format!("Dependency problems:\n{}", dependency_issues.join("\n")),
+                  tooltip::Position::FollowCursor
+                ).style(style::update::error::Tooltip).into()
+              } else {
+                Space::with_width(Length::Shrink).into()
+              })
+              .push(Space::with_width(Length::Units(5)))
             ).width(Length::FillPortion(name_portion)))
             .push(Container::new(Row::new()
               .push(Rule::vertical(0).style(style::max_rule::Rule))
               .push(Space::with_width(Length::Units(5)))
-              .push(Text::new(self.id.clone()).width(Length::Fill))
+              .push(Text::new(self.mod_info.id.clone()).width(Length::Fill))
             ).width(Length::FillPortion(id_portion)))
             .push(Container::new(Row::new()
               .push(Rule::vertical(0).style(style::max_rule::Rule))
               .push(Space::with_width(Length::Units(5)))
-              .push(Text::new(self.author.clone()).width(Length::Fill))
+              .push(Text::new(self.mod_info.author.clone()).width(Length::Fill))
             ).width(Length::FillPortion(author_portion)))
             .push::<Element<ModEntryMessage>>(
-              if let Some(status) = &self.update_status {
+              if let Some(status) = &self.mod_info.update_status {
+                // A status from the cache is marked until the mod has been checked again, with the details in the tooltip
+                let stale = if self.mod_info.remote_cached_at.is_some() { "*" } else { "" };
+
                 Container::new(
                   Tooltip::new(
                     Container::new(Row::with_children(vec![
                       Rule::vertical(0).style(style::max_rule::Rule).into(),
                       Space::with_width(Length::Units(5)).into(),
-                      if let UpdateStatus::Major(remote) | UpdateStatus::Minor(remote) | UpdateStatus::Patch(remote) = status {
+                      if let UpdateStatus::Major(remote) | UpdateStatus::Minor(remote) | UpdateStatus::Patch(remote) | UpdateStatus::Held(remote) = status {
                         Column::with_children(vec![
                           Space::with_height(Length::Units(5)).into(),
                           Row::with_children(vec![
                             Text::new(format!("Installed:")).into(),
                             Space::with_width(Length::Fill).into(),
-                            Text::new(self.version.to_string()).into(),
+                            Text::new(self.mod_info.version.to_string()).into(),
                           ]).width(Length::Fill).into(),
                           Space::with_height(Length::Fill).into(),
                           Row::with_children(vec![
                             Text::new(format!("Available:")).into(),
                             Space::with_width(Length::Fill).into(),
-                            Text::new(remote.to_string()).into(),
+                            Text::new(format!("{}{}", remote, stale)).into(),
                           ]).width(Length::Fill).into(),
                           Space::with_height(Length::Units(5)).into(),
                         ]).height(Length::Fill).width(Length::Fill).into()
                       } else {
-                        Text::new(self.version.to_string()).into()
+                        Text::new(format!("{}{}", self.mod_info.version, stale)).into()
                       },
                       Space::with_width(Length::Units(5)).into(),
                     ]))
-                    .style(status)
+                    .style(UpdateStatusContainerPatch(status.clone()))
                     .width(Length::Fill)
                     .height(Length::Fill),
-                    match status {
+                    format!("{}{}", match status {
                       UpdateStatus::Major(_) | UpdateStatus::Minor(_) => {
                         format!("{} update available", status)
                       },
                       UpdateStatus::Patch(_) => format!("Patch available"),
                       UpdateStatus::UpToDate => format!("Up to date!"),
                       UpdateStatus::Error => format!("Could not retrieve remote update data"),
-                      UpdateStatus::Discrepancy(_) => format!("Local is a higher version than remote")
-                    },
+                      UpdateStatus::Discrepancy(_) => format!("Local is a higher version than remote"),
+                      UpdateStatus::Held(_) => format!("Update available, but {}", self.mod_info.update_hold.as_ref()
+                        .map_or(String::from("ignored"), |hold| hold.to_string().to_lowercase()))
+                    }, self.mod_info.remote_cached_at.map_or(String::new(), |fetched_at| {
+                      format!("\n(Stale: as of {}, not yet checked again)", format_timestamp(fetched_at))
+                    }) + &bundled_updates(&self.mod_info).iter().map(|update| format!("\nBundled update: {}", update)).collect::<String>()),
                     tooltip::Position::FollowCursor
                   ).style(UpdateStatusTTPatch(status.clone()))
                 )
@@ -1188,7 +2237,7 @@ impl ModEntry {
                 Container::new(Row::new()
                   .push(Rule::vertical(0).style(style::max_rule::Rule))
                   .push(Space::with_width(Length::Units(5)))
-                  .push(Text::new(self.version.clone()).width(Length::Fill))
+                  .push(Text::new(self.mod_info.version.clone()).width(Length::Fill))
                 ).width(Length::FillPortion(mod_version_portion))
                 .into()
               }
@@ -1198,11 +2247,11 @@ impl ModEntry {
                 let game_version: Container<ModEntryMessage> = Container::new(Row::new()
                   .push(Rule::vertical(0).style(style::max_rule::Rule))
                   .push(Space::with_width(Length::Units(5)))
-                  .push(Text::new(self.game_version.clone()).width(Length::Fill)))
+                  .push(Text::new(self.mod_info.game_version.clone()).width(Length::Fill)))
                   .width(Length::Fill)
                   .height(Length::Fill);
 
-                match (self.parsed_game_version.clone(), starsector_version) {
+                match (self.mod_info.parsed_game_version.clone(), starsector_version) {
                   ((mod_major, ..), (game_major, ..)) if mod_major != game_major => {
                     Tooltip::new(
                       game_version.style(style::update::error::Container),
@@ -1270,145 +2319,50 @@ impl ModEntry {
       .push(Space::with_width(Length::Units(10)))
       .into()
   }
-
-  fn def_true() -> bool { true }
-
-  pub fn get_master_version(&self) -> Option<&ModVersionMeta> {
-    self.remote_version.as_ref()
-  }
-}
-
-  /**
-   * Parses a given version into a four-tuple of the assumed components.
-   * Assumptions:
-   * - The first component is always EITHER 0 and thus the major component OR it has been omitted and the first component is the minor component
-   * - If there are two components it is either the major and minor components OR minor and patch OR minor and RC (release candidate)
-   * - If there are three components it is either the major, minor and patch OR major, minor and RC OR minor, patch and RC
-   * - If there are four components then the first components MUST be 0 and MUST be the major component, and the following components 
-        are the minor, patch and RC components
-   */
-fn parse_game_version(text: &str) -> (Option<String>, Option<String>, Option<String>, Option<String>) {
-  let components: Vec<&str> = VERSION_REGEX.split(text).filter(|c| !c.is_empty()).collect();
-
-  match components.as_slice() {
-    [major, minor] if major == &"0" => {
-      // text = format!("{}.{}a", major, minor);
-      (Some(major.to_string()), Some(minor.to_string()), None, None)
-    }
-    [minor, patch_rc] => {
-      // text = format!("0.{}a-RC{}", minor, rc);
-      if text.contains("a-RC") {
-        (Some("0".to_string()), Some(minor.to_string()), None, Some(patch_rc.to_string()))
-      } else {
-        (Some("0".to_string()), Some(minor.to_string()), Some(patch_rc.to_string()), None)
-      }
-    }
-    [major, minor, patch_rc] if major == &"0" => {
-      // text = format!("{}.{}a-RC{}", major, minor, rc);
-      if text.contains("a-RC") {
-        (Some(major.to_string()), Some(minor.to_string()), None, Some(patch_rc.to_string()))
-      } else {
-        (Some(major.to_string()), Some(minor.to_string()), Some(patch_rc.to_string()), None)
-      }
-    }
-    [minor, patch, rc] => {
-      // text = format!("0.{}.{}a-RC{}", minor, patch, rc);
-      (Some("0".to_string()), Some(minor.to_string()), Some(patch.to_string()), Some(rc.to_string()))
-    }
-    [major, minor, patch, rc] if major == &"0" => {
-      // text = format!("{}.{}.{}a-RC{}", major, minor, patch, rc);
-      (Some(major.to_string()), Some(minor.to_string()), Some(patch.to_string()), Some(rc.to_string()))
-    }
-    _ => {
-      dbg!("Failed to normalise mod's quoted game version");
-      (None, None, None, None)
-    }
-  }
-}
-
-#[derive(Debug, Clone, Deserialize, Eq, Ord)]
-pub struct ModVersionMeta {
-  #[serde(alias="masterVersionFile")]
-  pub remote_url: String,
-  #[serde(alias="directDownloadURL")]
-  #[serde(default)]
-  pub direct_download_url: Option<String>,
-  #[serde(alias="modName")]
-  pub id: String,
-  #[serde(alias="modThreadId")]
-  #[serde(deserialize_with="deserialize_string_from_number")]
-  #[serde(default)]
-  fractal_id: String,
-  #[serde(alias="modNexusId")]
-  #[serde(deserialize_with="deserialize_string_from_number")]
-  #[serde(default)]
-  nexus_id: String,
-  #[serde(alias="modVersion")]
-  pub version: Version
-}
-
-impl PartialEq for ModVersionMeta {
-  fn eq(&self, other: &Self) -> bool {
-    self.id == other.id && self.version == other.version
-  }
-}
-
-impl PartialOrd for ModVersionMeta {
-  fn partial_cmp(&self, other: &Self) -> Option<std::cmp::Ordering> {
-    self.version.partial_cmp(&other.version)
-  }
-}
-
-#[derive(Debug, Clone, Deserialize, PartialEq, Eq, PartialOrd, Ord)]
-pub struct Version {
-  #[serde(deserialize_with="deserialize_number_from_string")]
-  pub major: i32,
-  #[serde(deserialize_with="deserialize_number_from_string")]
-  pub minor: i32,
-  #[serde(default)]
-  #[serde(deserialize_with="deserialize_string_from_number")]
-  pub patch: String
-}
-
-impl Display for Version {
-  fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::result::Result<(), std::fmt::Error> {
-    if self.patch.len() > 0 {
-      write!(f, "{}.{}.{}", self.major, self.minor, self.patch)
-    } else {
-      write!(f, "{}.{}", self.major, self.minor)
-    }
-  }
 }
 
 #[derive(Debug, Clone)]
 pub struct ModDescription {
-  pub mod_entry: Option<ModEntry>,
+  pub mod_entry: Option<mods::ModEntry>,
+  dependencies: Vec<(Dependency, DependencyStatus)>,
   fractal_link: button::State,
   nexus_link: button::State,
   file_link: button::State,
+  uninstall_button: button::State,
+  hold_version_button: button::State,
+  hold_all_button: button::State,
+  changelog_scroll: scrollable::State,
 }
 
 #[derive(Debug, Clone)]
 pub enum ModDescriptionMessage {
-  ModChanged(ModEntry),
+  ModChanged(mods::ModEntry, Vec<(Dependency, DependencyStatus)>),
   LinkClicked(String),
   FileClicked(PathBuf),
+  Uninstall(String),
+  SetUpdateHold(String, Option<UpdateHold>),
 }
 
 impl ModDescription {
   pub fn new() -> Self {
     ModDescription {
       mod_entry: None,
+      dependencies: vec![],
       fractal_link: button::State::new(),
       nexus_link: button::State::new(),
       file_link: button::State::new(),
+      uninstall_button: button::State::new(),
+      hold_version_button: button::State::new(),
+      hold_all_button: button::State::new(),
+      changelog_scroll: scrollable::State::new(),
     }
   }
 
   pub fn update(&mut self, message: ModDescriptionMessage) -> Command<ModDescriptionMessage> {
     match message {
-      ModDescriptionMessage::ModChanged(entry) => {
-        self.mod_entry = Some(entry)
+      ModDescriptionMessage::ModChanged(entry, dependencies) => {
+        self.mod_entry = Some(entry);
+        self.dependencies = dependencies;
       },
       ModDescriptionMessage::LinkClicked(url) => {
         if let Err(_) = opener::open(url) {
@@ -1419,7 +2373,9 @@ impl ModDescription {
         if let Err(_) = opener::open(path) {
           util::error(format!("Failed to open mod path."))
         }
-      }
+      },
+      ModDescriptionMessage::Uninstall(_) |
+      ModDescriptionMessage::SetUpdateHold(..) => {}
     }
 
     Command::none()
@@ -1459,6 +2415,63 @@ impl ModDescription {
         .push(Text::new(format!("{}", entry.version)).width(Length::FillPortion(4)))
         .into()
       );
+      if entry.total_conversion || entry.utility {
+        text.push(Row::new()
+          .push(Text::new(format!("Type:")).width(Length::FillPortion(1)))
+          .push(Text::new(if entry.total_conversion { "Total conversion" } else { "Utility" }).width(Length::FillPortion(4)))
+          .into()
+        );
+      }
+      if let Some(mod_plugin) = &entry.mod_plugin {
+        text.push(Row::new()
+          .push(Text::new(format!("Plugin:")).width(Length::FillPortion(1)))
+          .push(Text::new(mod_plugin.clone()).width(Length::FillPortion(4)))
+          .into()
+        );
+      }
+      if entry.jars.len() > 0 {
+        text.push(Row::new()
+          .push(Text::new(format!("Jars:")).width(Length::FillPortion(1)))
+          .push(Text::new(entry.jars.join("\n")).width(Length::FillPortion(4)))
+          .into()
+        );
+      }
+      if entry.bundled_versions.len() > 0 {
+        text.push(Row::new()
+          .push(Text::new(format!("Bundled:")).width(Length::FillPortion(1)))
+          .push(Column::with_children(entry.bundled_versions.iter().map(|bundled| {
+            let line = Text::new(match (&bundled.update_status, &bundled.remote) {
+              (Some(status), Some(remote)) if status.is_update_available() => {
+                format!("{} {} - {} update to {} available", bundled.name, bundled.local.version, status, remote.version)
+              },
+              (Some(status), _) => format!("{} {} - {}", bundled.name, bundled.local.version, status),
+              (None, _) => format!("{} {}", bundled.name, bundled.local.version),
+            });
+
+            if bundled.update_status.as_ref().map_or(false, UpdateStatus::is_update_available) {
+              line.color(iced::Color::from_rgb8(0xFF, 0xA0, 0x00))
+            } else {
+              line
+            }.into()
+          }).collect()).width(Length::FillPortion(4)))
+          .into()
+        );
+      }
+      if self.dependencies.len() > 0 {
+        text.push(Row::new()
+          .push(Text::new(format!("Dependencies:")).width(Length::FillPortion(1)))
+          .push(Column::with_children(self.dependencies.iter().map(|(dependency, status)| {
+            let line = Text::new(format!("{} - {}", dependency, status));
+
+            if *status == DependencyStatus::Satisfied {
+              line
+            } else {
+              line.color(iced::Color::from_rgb8(0xB0, 0x00, 0x20))
+            }.into()
+          }).collect()).width(Length::FillPortion(4)))
+          .into()
+        );
+      }
 
       if let (Some(version), _) | (None, Some(version)) = (&entry.remote_version, &entry.version_checker) {
         dbg!(version);
@@ -1506,11 +2519,79 @@ impl ModDescription {
         }
       }
 
+      if let Some(hold) = &entry.update_hold {
+        text.push(Row::new()
+          .push(Text::new(format!("Updates:")).width(Length::FillPortion(1)))
+          .push(Text::new(hold.to_string()).width(Length::FillPortion(4)))
+          .into()
+        );
+      }
+
+      let mut hold_buttons: Vec<Element<ModDescriptionMessage>> = vec![];
+      if entry.update_hold.is_some() {
+        hold_buttons.push(
+          Button::new(
+            &mut self.hold_all_button,
+            Text::new(format!("Stop ignoring updates"))
+          )
+          .width(Length::Shrink)
+          .on_press(ModDescriptionMessage::SetUpdateHold(entry.id.clone(), None))
+          .into()
+        );
+      } else if entry.version_checker.is_some() {
+        if let Some(UpdateStatus::Major(remote)) | Some(UpdateStatus::Minor(remote)) | Some(UpdateStatus::Patch(remote)) = &entry.update_status {
+          hold_buttons.push(
+            Button::new(
+              &mut self.hold_version_button,
+              Text::new(format!("Ignore this update"))
+            )
+            .width(Length::Shrink)
+            .on_press(ModDescriptionMessage::SetUpdateHold(entry.id.clone(), Some(UpdateHold::Version(remote.to_string()))))
+            .into()
+          );
+          hold_buttons.push(Space::with_width(Length::Units(5)).into());
+        }
+        hold_buttons.push(
+          Button::new(
+            &mut self.hold_all_button,
+            Text::new(format!("Ignore all updates"))
+          )
+          .width(Length::Shrink)
+          .on_press(ModDescriptionMessage::SetUpdateHold(entry.id.clone(), Some(UpdateHold::All)))
+          .into()
+        );
+      }
+      if hold_buttons.len() > 0 {
+        hold_buttons.push(Space::with_width(Length::Units(5)).into());
+      }
+
+      right.push(Text::new(format!("Description:")).into());
+      right.push(Text::new(entry.description.clone()).into());
+      if let Some(changelog) = &entry.changelog {
+        right.push(Space::with_height(Length::Units(10)).into());
+        right.push(Text::new(format!("Changelog:")).into());
+        right.push(match changelog {
+          Ok(changelog) => Scrollable::new(&mut self.changelog_scroll)
+            .push(Text::new(changelog.clone()).size(16))
+            .height(Length::Fill)
+            .into(),
+          Err(_) => Text::new(format!("Could not retrieve the changelog.")).color(iced::Color::from_rgb8(0xB0, 0x00, 0x20)).into()
+        });
+      }
+
       right.extend(vec![
-        Text::new(format!("Description:")).into(),
-        Text::new(entry.description.clone()).into(),
         Row::new()
           .push(Space::with_width(Length::Fill))
+          .push(Row::with_children(hold_buttons))
+          .push(
+            Button::new(
+              &mut self.uninstall_button,
+              Text::new(format!("Uninstall..."))
+            )
+            .width(Length::Shrink)
+            .on_press(ModDescriptionMessage::Uninstall(entry.id.clone()))
+          )
+          .push(Space::with_width(Length::Units(5)))
           .push(
             Button::new(
               &mut self.file_link,
@@ -1539,27 +2620,3 @@ impl ModDescription {
   const FRACTAL_URL: &'static str = "https://fractalsoftworks.com/forum/index.php?topic=";
   const NEXUS_URL: &'static str = "https://www.nexusmods.com/starsector/mods/";
 }
-
-#[derive(Serialize, Deserialize)]
-pub struct EnabledMods {
-  #[serde(rename = "enabledMods")]
-  enabled_mods: Vec<String>
-}
-
-impl EnabledMods {
-  pub async fn save(self, path: PathBuf) -> Result<(), SaveError> {
-    use tokio::fs;
-    use tokio::io::AsyncWriteExt;
-
-    let json = serde_json::to_string_pretty(&self)
-      .map_err(|_| SaveError::FormatError)?;
-
-    let mut file = fs::File::create(path)
-      .await
-      .map_err(|_| SaveError::FileError)?;
-
-    file.write_all(json.as_bytes())
-      .await
-      .map_err(|_| SaveError::WriteError)
-  }
-}
@@ -0,0 +1,175 @@
+use iced::{Button, button, Text, Row, Element, Command, Length, PickList, pick_list, TextInput, text_input, Align, Space};
+
+use starsector_mod_manager::{LoadError, SaveError};
+use starsector_mod_manager::profiles::Profiles;
+
+use crate::style;
+use crate::gui::util;
+
+#[derive(Debug, Clone)]
+pub enum ProfilesMessage {
+  Loaded(Result<Profiles, LoadError>),
+  Saved(Result<(), SaveError>),
+  Switch(String),
+  NameChanged(String),
+  Create,
+  Overwrite,
+  Rename,
+  Duplicate,
+  Delete,
+}
+
+pub struct ProfileControls {
+  pub profiles: Profiles,
+  pick_state: pick_list::State<String>,
+  name_state: text_input::State,
+  name: String,
+  create_state: button::State,
+  overwrite_state: button::State,
+  rename_state: button::State,
+  duplicate_state: button::State,
+  delete_state: button::State,
+}
+
+impl ProfileControls {
+  pub fn new() -> Self {
+    ProfileControls {
+      profiles: Profiles::default(),
+      pick_state: pick_list::State::default(),
+      name_state: text_input::State::default(),
+      name: String::new(),
+      create_state: button::State::new(),
+      overwrite_state: button::State::new(),
+      rename_state: button::State::new(),
+      duplicate_state: button::State::new(),
+      delete_state: button::State::new(),
+    }
+  }
+
+  /**
+   * `enabled_mods` is the current set of enabled mods, used when creating or overwriting a profile.
+   * Switching profiles touches the mod list as well, so ModList handles `Switch` itself before this is called.
+   */
+  pub fn update(&mut self, message: ProfilesMessage, enabled_mods: Vec<String>) -> Command<ProfilesMessage> {
+    let res = match message {
+      ProfilesMessage::Loaded(res) => {
+        match res {
+          Ok(profiles) => self.profiles = profiles,
+          Err(err) => {
+            dbg!("{:?}", err);
+            util::error("Failed to load mod profiles.");
+          }
+        }
+
+        return Command::none()
+      },
+      ProfilesMessage::Saved(res) => {
+        if let Err(err) = res {
+          dbg!("{:?}", err);
+          util::error("Failed to save mod profiles.");
+        }
+
+        return Command::none()
+      },
+      ProfilesMessage::NameChanged(name) => {
+        self.name = name;
+
+        return Command::none()
+      },
+      ProfilesMessage::Switch(name) => {
+        self.profiles.active = Some(name);
+
+        Ok(())
+      },
+      ProfilesMessage::Create => self.profiles.create(self.name.clone(), enabled_mods),
+      ProfilesMessage::Overwrite => {
+        match self.profiles.active.clone() {
+          Some(active) if util::query(format!("Overwrite profile \"{}\" with the currently enabled mods?", active)) => {
+            self.profiles.overwrite(&active, enabled_mods)
+          },
+          _ => return Command::none()
+        }
+      },
+      ProfilesMessage::Rename => {
+        match self.profiles.active.clone() {
+          Some(active) => self.profiles.rename(&active, self.name.clone()),
+          None => return Command::none()
+        }
+      },
+      ProfilesMessage::Duplicate => {
+        match self.profiles.active.clone() {
+          Some(active) => self.profiles.duplicate(&active, self.name.clone()),
+          None => return Command::none()
+        }
+      },
+      ProfilesMessage::Delete => {
+        match self.profiles.active.clone() {
+          Some(active) if util::query(format!("Delete profile \"{}\"? Your installed and enabled mods will not be changed.", active)) => {
+            self.profiles.delete(&active)
+          },
+          _ => return Command::none()
+        }
+      },
+    };
+
+    match res {
+      Ok(()) => {
+        self.name.clear();
+
+        Command::perform(self.profiles.clone().save(), ProfilesMessage::Saved)
+      },
+      Err(err) => {
+        util::error(err.to_string());
+
+        Command::none()
+      }
+    }
+  }
+
+  pub fn view(&mut self) -> Element<ProfilesMessage> {
+    let has_active = self.profiles.active.is_some();
+    let has_name = !self.name.trim().is_empty();
+
+    let mut overwrite = Button::new(&mut self.overwrite_state, Text::new("Save")).style(style::button_only_hover::Button).padding(5);
+    let mut rename = Button::new(&mut self.rename_state, Text::new("Rename")).style(style::button_only_hover::Button).padding(5);
+    let mut duplicate = Button::new(&mut self.duplicate_state, Text::new("Duplicate")).style(style::button_only_hover::Button).padding(5);
+    let mut delete = Button::new(&mut self.delete_state, Text::new("Delete")).style(style::button_only_hover::Button).padding(5);
+    let mut create = Button::new(&mut self.create_state, Text::new("Save As New")).style(style::button_only_hover::Button).padding(5);
+
+    if has_active {
+      overwrite = overwrite.on_press(ProfilesMessage::Overwrite);
+      delete = delete.on_press(ProfilesMessage::Delete);
+      if has_name {
+        rename = rename.on_press(ProfilesMessage::Rename);
+        duplicate = duplicate.on_press(ProfilesMessage::Duplicate);
+      }
+    }
+    if has_name {
+      create = create.on_press(ProfilesMessage::Create);
+    }
+
+    Row::new()
+      .push(Text::new("Profile:"))
+      .push(PickList::new(
+        &mut self.pick_state,
+        self.profiles.names(),
+        self.profiles.active.clone(),
+        ProfilesMessage::Switch
+      ).width(Length::Units(200)))
+      .push(overwrite)
+      .push(delete)
+      .push(Space::with_width(Length::Units(10)))
+      .push(TextInput::new(
+        &mut self.name_state,
+        "Profile name",
+        &self.name,
+        ProfilesMessage::NameChanged
+      ).padding(5).width(Length::Units(200)))
+      .push(create)
+      .push(rename)
+      .push(duplicate)
+      .spacing(5)
+      .align_items(Align::Center)
+      .into()
+  }
+}
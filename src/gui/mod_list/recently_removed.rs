@@ -0,0 +1,174 @@
+use iced::{Button, button, Text, Row, Column, Element, Command, Length, Scrollable, scrollable, Align, Space};
+
+use starsector_mod_manager::trash::{self, TrashEntry};
+use starsector_mod_manager::util::format_timestamp;
+
+use crate::style;
+use crate::gui::util;
+
+#[derive(Debug, Clone)]
+pub enum RecentlyRemovedMessage {
+  Load,
+  Loaded(Vec<TrashEntry>),
+  Purge(u32),
+  Restore(usize),
+  Restored(Result<String, String>),
+  Delete(usize),
+  Deleted(Result<(), String>),
+}
+
+struct RemovedMod {
+  entry: TrashEntry,
+  restore_state: button::State,
+  delete_state: button::State,
+}
+
+/**
+ * The mods that have been uninstalled or replaced and are still in the trash.
+ */
+pub struct RecentlyRemoved {
+  entries: Vec<RemovedMod>,
+  scroll: scrollable::State,
+}
+
+impl RecentlyRemoved {
+  pub fn new() -> Self {
+    RecentlyRemoved {
+      entries: vec![],
+      scroll: scrollable::State::new(),
+    }
+  }
+
+  /**
+   * A restored mod needs the mod list to be re-parsed, so ModList watches for `Restored` itself after this is called.
+   */
+  pub fn update(&mut self, message: RecentlyRemovedMessage) -> Command<RecentlyRemovedMessage> {
+    match message {
+      RecentlyRemovedMessage::Load => {
+        Command::perform(TrashEntry::list(), RecentlyRemovedMessage::Loaded)
+      },
+      RecentlyRemovedMessage::Loaded(entries) => {
+        self.entries = entries.into_iter()
+          .map(|entry| RemovedMod {
+            entry,
+            restore_state: button::State::new(),
+            delete_state: button::State::new(),
+          })
+          .collect();
+
+        Command::none()
+      },
+      RecentlyRemovedMessage::Purge(retention_days) => {
+        Command::perform(async move {
+          trash::purge(retention_days).await;
+
+          TrashEntry::list().await
+        }, RecentlyRemovedMessage::Loaded)
+      },
+      RecentlyRemovedMessage::Restore(idx) => {
+        let entry = if let Some(row) = self.entries.get(idx) {
+          row.entry.clone()
+        } else {
+          return Command::none();
+        };
+
+        let replace_existing = entry.is_blocked();
+        if replace_existing && !util::query(format!(
+          "`{}` is occupied by another mod, likely the version that replaced this one. Do you want to move it to Recently Removed and restore {} {}?",
+          entry.original_path.display(),
+          entry.name,
+          entry.version
+        )) {
+          return Command::none();
+        }
+
+        Command::perform(async move {
+          let name = entry.name.clone();
+
+          entry.restore(replace_existing).await
+            .map(|_| name)
+            .map_err(|err| err.to_string())
+        }, RecentlyRemovedMessage::Restored)
+      },
+      RecentlyRemovedMessage::Restored(res) => {
+        match res {
+          Ok(name) => util::notif(format!("Successfully restored {}.", name)),
+          Err(err) => util::error(format!("Failed to restore mod:\n{}", err))
+        }
+
+        Command::perform(TrashEntry::list(), RecentlyRemovedMessage::Loaded)
+      },
+      RecentlyRemovedMessage::Delete(idx) => {
+        let entry = if let Some(row) = self.entries.get(idx) {
+          row.entry.clone()
+        } else {
+          return Command::none();
+        };
+
+        if !util::query(format!("Permanently delete {} {}? This cannot be undone.", entry.name, entry.version)) {
+          return Command::none();
+        }
+
+        Command::perform(async move {
+          entry.delete().await.map_err(|err| err.to_string())
+        }, RecentlyRemovedMessage::Deleted)
+      },
+      RecentlyRemovedMessage::Deleted(res) => {
+        if let Err(err) = res {
+          util::error(format!("Failed to delete mod:\n{}", err));
+        }
+
+        Command::perform(TrashEntry::list(), RecentlyRemovedMessage::Loaded)
+      }
+    }
+  }
+
+  pub fn view(&mut self) -> Element<RecentlyRemovedMessage> {
+    if self.entries.len() == 0 {
+      return Text::new("No mods have been removed recently.").color([0.7, 0.7, 0.7]).into()
+    }
+
+    let rows: Vec<Element<RecentlyRemovedMessage>> = self.entries.iter_mut()
+      .enumerate()
+      .map(|(idx, row)| {
+        Row::new()
+          .push(Text::new(row.entry.name.clone()).width(Length::FillPortion(3)))
+          .push(Text::new(row.entry.id.clone()).width(Length::FillPortion(3)))
+          .push(Text::new(row.entry.version.clone()).width(Length::FillPortion(2)))
+          .push(Text::new(format_timestamp(row.entry.removed_at)).width(Length::FillPortion(3)))
+          .push(Text::new(row.entry.reason.to_string()).width(Length::FillPortion(2)))
+          .push(Button::new(&mut row.restore_state, Text::new("Restore"))
+            .on_press(RecentlyRemovedMessage::Restore(idx))
+            .style(style::button_only_hover::Button)
+            .padding(5)
+          )
+          .push(Button::new(&mut row.delete_state, Text::new("Delete"))
+            .on_press(RecentlyRemovedMessage::Delete(idx))
+            .style(style::button_only_hover::Button)
+            .padding(5)
+          )
+          .push(Space::with_width(Length::Units(10)))
+          .spacing(5)
+          .align_items(Align::Center)
+          .into()
+      })
+      .collect();
+
+    let heading = Row::new()
+      .push(Text::new("Name").width(Length::FillPortion(3)))
+      .push(Text::new("ID").width(Length::FillPortion(3)))
+      .push(Text::new("Version").width(Length::FillPortion(2)))
+      .push(Text::new("Removed").width(Length::FillPortion(3)))
+      .push(Text::new("Reason").width(Length::FillPortion(2)))
+      .push(Space::with_width(Length::Units(160)))
+      .spacing(5);
+
+    Column::new()
+      .push(heading)
+      .push(Scrollable::new(&mut self.scroll)
+        .push(Column::with_children(rows))
+        .height(Length::Fill)
+      )
+      .into()
+  }
+}
@@ -1,12 +1,17 @@
 use iced::{
   Align, Button, Length, Text, TextInput, Command, Row, Column, Element,
-  text_input, button, Container, Space, Checkbox, PickList, pick_list
+  text_input, button, Container, Space, Checkbox, PickList, pick_list, Scrollable, scrollable
 };
 use tinyfiledialogs as tfd;
-use std::path::PathBuf;
+use std::{path::PathBuf, time::Duration};
 use directories::UserDirs;
 
-pub mod vmparams;
+pub use starsector_mod_manager::vmparams;
+use starsector_mod_manager::archive_cache::{self, CachedArchive};
+use starsector_mod_manager::installer;
+use starsector_mod_manager::util::format_timestamp;
+
+use crate::style;
 
 #[derive(Debug, Clone)]
 pub struct Settings {
@@ -29,6 +34,28 @@ pub struct Settings {
   pub experimental_resolution: (u32, u32),
   horizontal_res_input_state: text_input::State,
   vertical_res_input_state: text_input::State,
+  pub trash_retention_days: u32,
+  trash_retention_input_state: text_input::State,
+  pub max_concurrent_installs: u32,
+  max_concurrent_installs_input_state: text_input::State,
+  pub download_retries: u32,
+  download_retries_input_state: text_input::State,
+  pub download_timeout_secs: u64,
+  download_timeout_input_state: text_input::State,
+  pub archive_cache_mb: u64,
+  archive_cache_input_state: text_input::State,
+  cached_archives: Vec<CachedArchiveRow>,
+  show_cache: bool,
+  cache_scroll: scrollable::State,
+  prune_cache_state: button::State,
+  clear_cache_state: button::State,
+}
+
+#[derive(Debug, Clone)]
+struct CachedArchiveRow {
+  archive: CachedArchive,
+  install_state: button::State,
+  remove_state: button::State,
 }
 
 #[derive(Debug, Clone)]
@@ -47,6 +74,18 @@ pub enum SettingsMessage {
   GitWarnToggled(bool),
   ExperimentalLaunchToggled(bool),
   ResolutionChanged((String, String)),
+  TrashRetentionChanged(String),
+  MaxConcurrentInstallsChanged(String),
+  DownloadRetriesChanged(String),
+  DownloadTimeoutChanged(String),
+  ArchiveCacheSizeChanged(String),
+  LoadCache,
+  CacheLoaded(Vec<CachedArchive>),
+  ShowCacheToggled(bool),
+  InstallCached(usize),
+  RemoveCached(usize),
+  PruneCache,
+  ClearCache,
 }
 
 #[derive(Debug, Clone)]
@@ -78,9 +117,31 @@ impl Settings {
       experimental_resolution: (1280, 768),
       horizontal_res_input_state: text_input::State::new(),
       vertical_res_input_state: text_input::State::new(),
+      trash_retention_days: 14,
+      trash_retention_input_state: text_input::State::new(),
+      max_concurrent_installs: 2,
+      max_concurrent_installs_input_state: text_input::State::new(),
+      download_retries: 5,
+      download_retries_input_state: text_input::State::new(),
+      download_timeout_secs: 30,
+      download_timeout_input_state: text_input::State::new(),
+      archive_cache_mb: 2048,
+      archive_cache_input_state: text_input::State::new(),
+      cached_archives: vec![],
+      show_cache: false,
+      cache_scroll: scrollable::State::new(),
+      prune_cache_state: button::State::new(),
+      clear_cache_state: button::State::new(),
     }
   }
 
+  /**
+   * Where the archive in the given row of the archive cache list is stored.
+   */
+  pub fn cached_archive_path(&self, idx: usize) -> Option<PathBuf> {
+    self.cached_archives.get(idx).map(|row| row.archive.path.clone())
+  }
+
   pub fn update(&mut self, message: SettingsMessage) -> Command<SettingsMessage> {
     match message {
       SettingsMessage::ExperimentalLaunchToggled(val) => {
@@ -102,6 +163,118 @@ impl Settings {
 
         Command::none()
       }
+      SettingsMessage::TrashRetentionChanged(days) => {
+        self.trash_retention_days = if days.len() == 0 {
+          0
+        } else {
+          days.parse::<u32>().unwrap_or(self.trash_retention_days)
+        };
+
+        Command::none()
+      }
+      SettingsMessage::MaxConcurrentInstallsChanged(limit) => {
+        // At least one install has to be able to run, otherwise the queue never moves
+        self.max_concurrent_installs = if limit.len() == 0 {
+          1
+        } else {
+          limit.parse::<u32>().unwrap_or(self.max_concurrent_installs).max(1)
+        };
+
+        Command::none()
+      }
+      SettingsMessage::DownloadRetriesChanged(retries) => {
+        self.download_retries = if retries.len() == 0 {
+          0
+        } else {
+          retries.parse::<u32>().unwrap_or(self.download_retries)
+        };
+
+        Command::none()
+      }
+      SettingsMessage::DownloadTimeoutChanged(secs) => {
+        self.download_timeout_secs = if secs.len() == 0 {
+          1
+        } else {
+          secs.parse::<u64>().unwrap_or(self.download_timeout_secs).max(1)
+        };
+
+        Command::none()
+      }
+      SettingsMessage::ArchiveCacheSizeChanged(megabytes) => {
+        self.archive_cache_mb = if megabytes.len() == 0 {
+          0
+        } else {
+          megabytes.parse::<u64>().unwrap_or(self.archive_cache_mb)
+        };
+
+        Command::none()
+      }
+      SettingsMessage::LoadCache => {
+        Command::perform(archive_cache::list(), SettingsMessage::CacheLoaded)
+      }
+      SettingsMessage::CacheLoaded(archives) => {
+        self.cached_archives = archives.into_iter()
+          .map(|archive| CachedArchiveRow {
+            archive,
+            install_state: button::State::new(),
+            remove_state: button::State::new(),
+          })
+          .collect();
+
+        Command::none()
+      }
+      SettingsMessage::ShowCacheToggled(show) => {
+        self.show_cache = show;
+
+        Command::none()
+      }
+      SettingsMessage::InstallCached(idx) => {
+        // Installing is left to the mod list, this only marks the archive as used
+        if let Some(row) = self.cached_archives.get(idx) {
+          let (id, version) = (row.archive.id.clone(), row.archive.version.clone());
+
+          Command::perform(async move {
+            archive_cache::get(&id, &version).await;
+
+            archive_cache::list().await
+          }, SettingsMessage::CacheLoaded)
+        } else {
+          Command::none()
+        }
+      }
+      SettingsMessage::RemoveCached(idx) => {
+        if let Some(row) = self.cached_archives.get(idx) {
+          let (id, version) = (row.archive.id.clone(), row.archive.version.clone());
+
+          Command::perform(async move {
+            if let Err(err) = archive_cache::remove(&id, &version).await {
+              dbg!(err);
+            }
+
+            archive_cache::list().await
+          }, SettingsMessage::CacheLoaded)
+        } else {
+          Command::none()
+        }
+      }
+      SettingsMessage::PruneCache => {
+        let limit = self.archive_cache_mb.saturating_mul(1024 * 1024);
+
+        Command::perform(async move {
+          archive_cache::prune(limit).await;
+          installer::prune_partials(installer::STALE_PARTIAL_AGE).await;
+
+          archive_cache::list().await
+        }, SettingsMessage::CacheLoaded)
+      }
+      SettingsMessage::ClearCache => {
+        Command::perform(async {
+          archive_cache::clear().await;
+          installer::prune_partials(Duration::from_secs(0)).await;
+
+          archive_cache::list().await
+        }, SettingsMessage::CacheLoaded)
+      }
       SettingsMessage::GitWarnToggled(val) => {
         self.git_warn = val;
 
@@ -256,6 +429,93 @@ impl Settings {
         .width(Length::Fill)
         .padding(2)
         .into(),
+      Row::new()
+        .push(Text::new("Days to keep removed mods (0 keeps them forever):").width(Length::FillPortion(3)))
+        .push(TextInput::new(
+          &mut self.trash_retention_input_state,
+          "",
+          &self.trash_retention_days.to_string(),
+          SettingsMessage::TrashRetentionChanged
+        ).padding(5).width(Length::FillPortion(2)))
+        .push(Space::with_width(Length::FillPortion(5)))
+        .width(Length::Fill)
+        .align_items(Align::Center)
+        .padding(2)
+        .into(),
+      Row::new()
+        .push(Text::new("Mods to install at the same time:").width(Length::FillPortion(3)))
+        .push(TextInput::new(
+          &mut self.max_concurrent_installs_input_state,
+          "",
+          &self.max_concurrent_installs.to_string(),
+          SettingsMessage::MaxConcurrentInstallsChanged
+        ).padding(5).width(Length::FillPortion(2)))
+        .push(Space::with_width(Length::FillPortion(5)))
+        .width(Length::Fill)
+        .align_items(Align::Center)
+        .padding(2)
+        .into(),
+      Row::new()
+        .push(Text::new("Times to retry a failed download:").width(Length::FillPortion(3)))
+        .push(TextInput::new(
+          &mut self.download_retries_input_state,
+          "",
+          &self.download_retries.to_string(),
+          SettingsMessage::DownloadRetriesChanged
+        ).padding(5).width(Length::FillPortion(2)))
+        .push(Space::with_width(Length::FillPortion(5)))
+        .width(Length::Fill)
+        .align_items(Align::Center)
+        .padding(2)
+        .into(),
+      Row::new()
+        .push(Text::new("Seconds to wait on a stalled download:").width(Length::FillPortion(3)))
+        .push(TextInput::new(
+          &mut self.download_timeout_input_state,
+          "",
+          &self.download_timeout_secs.to_string(),
+          SettingsMessage::DownloadTimeoutChanged
+        ).padding(5).width(Length::FillPortion(2)))
+        .push(Space::with_width(Length::FillPortion(5)))
+        .width(Length::Fill)
+        .align_items(Align::Center)
+        .padding(2)
+        .into(),
+      Row::new()
+        .push(Text::new("Archive cache size in MB (0 disables it):").width(Length::FillPortion(3)))
+        .push(TextInput::new(
+          &mut self.archive_cache_input_state,
+          "",
+          &self.archive_cache_mb.to_string(),
+          SettingsMessage::ArchiveCacheSizeChanged
+        ).padding(5).width(Length::FillPortion(2)))
+        .push(Space::with_width(Length::FillPortion(5)))
+        .width(Length::Fill)
+        .align_items(Align::Center)
+        .padding(2)
+        .into(),
+      Row::new()
+        .push(Text::new(format!(
+          "Cached archives: {} ({:.1} MB)",
+          self.cached_archives.len(),
+          self.cached_archives.iter().map(|row| row.archive.size).sum::<u64>() as f64 / (1024.0 * 1024.0)
+        )).width(Length::FillPortion(3)))
+        .push(Row::new()
+          .push(Checkbox::new(self.show_cache, "Show", SettingsMessage::ShowCacheToggled))
+          .push(Button::new(&mut self.prune_cache_state, Text::new("Prune to size"))
+            .on_press(SettingsMessage::PruneCache)
+          )
+          .push(Button::new(&mut self.clear_cache_state, Text::new("Clear"))
+            .on_press(SettingsMessage::ClearCache)
+          )
+          .spacing(10)
+          .align_items(Align::Center)
+          .width(Length::FillPortion(7))
+        )
+        .width(Length::Fill)
+        .align_items(Align::Center)
+        .padding(2)
+        .into(),
       Row::new()
         .push(Text::new("Enable VM params editing:").width(Length::FillPortion(3)))
         .push(Checkbox::new(
@@ -268,6 +528,40 @@ impl Settings {
         .into()
     ];
 
+    if self.show_cache && self.cached_archives.len() > 0 {
+      let rows: Vec<Element<SettingsMessage>> = self.cached_archives.iter_mut()
+        .enumerate()
+        .map(|(idx, row)| {
+          Row::new()
+            .push(Text::new(row.archive.id.clone()).width(Length::FillPortion(3)))
+            .push(Text::new(row.archive.version.clone()).width(Length::FillPortion(2)))
+            .push(Text::new(format!("{:.1} MB", row.archive.size as f64 / (1024.0 * 1024.0))).width(Length::FillPortion(2)))
+            .push(Text::new(format!("Last used {}", format_timestamp(row.archive.last_used))).width(Length::FillPortion(4)))
+            .push(Button::new(&mut row.install_state, Text::new("Install"))
+              .on_press(SettingsMessage::InstallCached(idx))
+              .style(style::button_only_hover::Button)
+              .padding(2)
+            )
+            .push(Button::new(&mut row.remove_state, Text::new("Remove"))
+              .on_press(SettingsMessage::RemoveCached(idx))
+              .style(style::button_only_hover::Button)
+              .padding(2)
+            )
+            .spacing(5)
+            .align_items(Align::Center)
+            .into()
+        })
+        .collect();
+
+      controls.push(
+        Scrollable::new(&mut self.cache_scroll)
+          .push(Column::with_children(rows))
+          .max_height(150)
+          .padding(2)
+          .into()
+      );
+    }
+
     if self.vmparams_editing_enabled {
       if let Some(vmparams) = &self.vmparams {
         controls.push(
@@ -433,5 +727,5 @@ THE SOFTWARE IS PROVIDED `AS IS`, WITHOUT WARRANTY OF ANY KIND, EXPRESS OR IMPLI
 
 This program makes use of multiple open source components and framewords. They include, and are not limited to:
 
-infer, tokio, iced, iced_native, iced_aw, tinyfiledialogs, native-dialog, iced_futures, serde, serde_json, json5, json_comments, if_chain, reqwest, serde-aux, handwritten-json, unrar, opener, directories, tempfile, compress-tools, snafu, remove_dir_all, sublime_fuzzy, classfile-parser, zip, regex, lazy_static
+infer, tokio, iced, iced_native, iced_aw, tinyfiledialogs, native-dialog, iced_futures, serde, serde_json, json5, json_comments, if_chain, reqwest, serde-aux, handwritten-json, opener, directories, tempfile, compress-tools, snafu, remove_dir_all, sublime_fuzzy, classfile-parser, zip, regex, lazy_static
 "#;
@@ -0,0 +1,1069 @@
+use std::path::{Path, PathBuf, Component};
+use std::{
+  fs::{copy, create_dir_all, read_dir},
+  io, io::Write,
+  fmt::Display,
+  collections::HashSet,
+  time::Duration,
+};
+use std::sync::{Arc, Mutex, atomic::{AtomicBool, Ordering}};
+use tokio::{task, fs::rename};
+use tempfile::{tempdir, TempDir};
+use snafu::{Snafu, ResultExt, OptionExt};
+// use find_mountpoint::find_mountpoint;
+use remove_dir_all::remove_dir_all;
+use lazy_static::lazy_static;
+use reqwest::{StatusCode, header};
+use ring::digest;
+use serde::{Serialize, Deserialize};
+
+use crate::mods::{ModEntry, ModVersionMeta};
+use crate::trash::{self, RemovalReason};
+use crate::archive_cache;
+use crate::archive::{self, EntryVisitor, EntryHeader, EntryKind};
+use crate::config::{Config, config_dir};
+
+/**
+ * Report progress at most once per this many bytes downloaded or copied, so large mods don't flood the receiver.
+ */
+const REPORT_BYTES: u64 = 1 << 20;
+/**
+ * A mod_info.json larger than this isn't kept in memory when previewing an archive, and so counts as unreadable.
+ */
+const MAX_PREVIEW_MOD_INFO: usize = 1 << 20;
+
+/**
+ * How far along one step of an installation is. Totals are `None` when they aren't known up front, such as when a server
+ * doesn't send a Content-Length.
+ */
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum InstallProgress {
+  Downloading { received: u64, total: Option<u64> },
+  Extracting { entries: u64, total: Option<u64> },
+  Copying { copied: u64, total: u64 },
+}
+
+impl InstallProgress {
+  /**
+   * How much of the current step is done, from 0 to 1, if its total is known.
+   */
+  pub fn fraction(&self) -> Option<f32> {
+    let (done, total) = match *self {
+      InstallProgress::Downloading { received, total } => (received, total?),
+      InstallProgress::Extracting { entries, total } => (entries, total?),
+      InstallProgress::Copying { copied, total } => (copied, total),
+    };
+
+    if total == 0 {
+      Some(1.0)
+    } else {
+      Some((done as f64 / total as f64).min(1.0) as f32)
+    }
+  }
+}
+
+impl Display for InstallProgress {
+  fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::result::Result<(), std::fmt::Error> {
+    let megabytes = |bytes: u64| bytes as f64 / (1024.0 * 1024.0);
+
+    match *self {
+      InstallProgress::Downloading { received, total: Some(total) } => write!(f, "Downloading {:.1} / {:.1} MB", megabytes(received), megabytes(total)),
+      InstallProgress::Downloading { received, total: None } => write!(f, "Downloading {:.1} MB", megabytes(received)),
+      InstallProgress::Extracting { entries, total: Some(total) } => write!(f, "Extracting {} / {} files", entries, total),
+      InstallProgress::Extracting { entries, total: None } => write!(f, "Extracting {} files", entries),
+      InstallProgress::Copying { copied, total } => write!(f, "Copying {:.1} / {:.1} MB", megabytes(copied), megabytes(total)),
+    }
+  }
+}
+
+/**
+ * Receives progress updates from long running installer operations, and lets the receiver cancel them.
+ * The default reporter discards updates and is never cancelled.
+ */
+#[derive(Clone, Default)]
+pub struct ProgressReporter {
+  on_progress: Option<Arc<dyn Fn(InstallProgress) + Send + Sync>>,
+  cancelled: Arc<AtomicBool>,
+}
+
+impl ProgressReporter {
+  pub fn new<F: Fn(InstallProgress) + Send + Sync + 'static>(on_progress: F) -> Self {
+    ProgressReporter {
+      on_progress: Some(Arc::new(on_progress)),
+      cancelled: Arc::new(AtomicBool::new(false)),
+    }
+  }
+
+  /**
+   * Setting the given flag cancels any operation using this reporter at its next opportunity, failing it with `InstallError::Cancelled`.
+   * Temporary files and folders are cleaned up, and the mods folder is left as it was.
+   */
+  pub fn cancellable(mut self, cancelled: Arc<AtomicBool>) -> Self {
+    self.cancelled = cancelled;
+
+    self
+  }
+
+  pub fn report(&self, progress: InstallProgress) {
+    if let Some(on_progress) = &self.on_progress {
+      on_progress(progress)
+    }
+  }
+
+  pub fn is_cancelled(&self) -> bool {
+    self.cancelled.load(Ordering::Relaxed)
+  }
+
+  fn check_cancelled(&self) -> Result<(), InstallError> {
+    if self.is_cancelled() {
+      Err(InstallError::Cancelled)
+    } else {
+      Ok(())
+    }
+  }
+}
+
+/**
+ * Decompresses an archive (or takes a folder as-is), locates the mod inside it and parses its mod_info.json.
+ * If there's more than one mod inside, this is the first of them, see `stage_all`.
+ * The returned HybridPath keeps any temporary directory alive for as long as it is held.
+ */
+pub async fn stage(path: PathBuf, progress: ProgressReporter) -> Result<(HybridPath, ModEntry), InstallError> {
+  let mut mods = stage_all(path, progress).await?;
+
+  Ok(mods.remove(0))
+}
+
+/**
+ * As with `stage`, but for every mod in the archive or folder, such as the libraries in a library pack.
+ * Folders with an unreadable mod_info.json are skipped, unless there are no readable ones at all. Never empty.
+ */
+pub async fn stage_all(path: PathBuf, progress: ProgressReporter) -> Result<Vec<(HybridPath, ModEntry)>, InstallError> {
+  progress.check_cancelled()?;
+
+  let root = if path.is_file() {
+    let temp = task::spawn_blocking(move || decompress(path, &progress)).await.expect("Run decompression")?;
+
+    HybridPath::Temp(Arc::new(temp), None)
+  } else {
+    HybridPath::PathBuf(path)
+  };
+
+  let dir = root.get_path_copy();
+  let mods: Vec<(HybridPath, ModEntry)> = task::spawn_blocking(move || find_nested_mods(&dir)).await
+    .expect("Find mods in given folder")
+    .context(Io {})?
+    .into_iter()
+    .filter_map(|mod_path| {
+      let mod_info = ModEntry::from_file(&mod_path).ok()?;
+      let mod_folder = match &root {
+        HybridPath::PathBuf(_) => HybridPath::PathBuf(mod_path),
+        HybridPath::Temp(temp, _) => HybridPath::Temp(temp.clone(), Some(mod_path))
+      };
+
+      Some((mod_folder, mod_info))
+    })
+    .collect();
+
+  if mods.is_empty() {
+    return Err(InstallError::Any { detail: String::from("Could not find mod folder or parse mod_info file.") })
+  }
+
+  Ok(mods)
+}
+
+/**
+ * Extracts an archive, in whichever supported format it's in, into a new temporary folder.
+ */
+pub fn decompress(path: PathBuf, progress: &ProgressReporter) -> Result<TempDir, InstallError> {
+  let format = archive::format_of(&path)?;
+  let temp_dir = tempdir().context(Io {})?;
+  format.extract(&path, temp_dir.path(), progress)?;
+
+  Ok(temp_dir)
+}
+
+/**
+ * What installing an archive or folder would do, worked out without writing anything to disk.
+ */
+#[derive(Debug, Clone)]
+pub struct ArchivePreview {
+  pub mods: Vec<PreviewedMod>,
+  /**
+   * Uncompressed size of every file in the archive or folder, in bytes.
+   */
+  pub size: u64,
+}
+
+#[derive(Debug, Clone)]
+pub struct PreviewedMod {
+  /**
+   * The mod's folder, relative to the root of the archive or folder.
+   */
+  pub folder: PathBuf,
+  pub mod_info: ModEntry,
+  /**
+   * The installed mod, or other folder, that installing this mod would replace. Filled in by `mark_replacements`.
+   */
+  pub replaces: Option<PathBuf>,
+}
+
+impl ArchivePreview {
+  /**
+   * Sets what each mod would replace, as found by `existing` from the mod's ID.
+   */
+  pub fn mark_replacements(&mut self, existing: impl Fn(&str) -> Option<PathBuf>) {
+    for previewed in &mut self.mods {
+      previewed.replaces = existing(&previewed.mod_info.id);
+    }
+  }
+}
+
+/**
+ * Lists the mods an archive or folder holds, the same ones `stage_all` would find, along with its total size.
+ * Archives are read through once in memory rather than extracted, so nothing is written anywhere.
+ */
+pub async fn preview(path: PathBuf) -> Result<ArchivePreview, InstallError> {
+  task::spawn_blocking(move || if path.is_file() {
+    preview_archive(&path)
+  } else {
+    preview_folder(&path)
+  }).await.expect("Run archive preview")
+}
+
+fn preview_archive(path: &Path) -> Result<ArchivePreview, InstallError> {
+  let mut collector = PreviewCollector::default();
+  archive::format_of(path)?.read(path, &mut collector)?;
+
+  // As with `find_nested_mods`, a mod_info.json inside another mod's folder belongs to that mod
+  let mut mod_infos = collector.mod_infos;
+  mod_infos.sort_by(|(left, _), (right, _)| left.cmp(right));
+  let mut roots: Vec<PathBuf> = vec![];
+  let mods = mod_infos.into_iter()
+    .filter_map(|(folder, data)| {
+      if roots.iter().any(|root| folder.starts_with(root)) {
+        return None
+      }
+      roots.push(folder.clone());
+
+      let mod_info = ModEntry::from_mod_info(&String::from_utf8_lossy(&data)).ok()?;
+
+      Some(PreviewedMod { folder, mod_info, replaces: None })
+    })
+    .collect();
+
+  Ok(ArchivePreview { mods, size: collector.size })
+}
+
+/**
+ * Totals up the size of an archive's files and keeps the contents of any mod_info.json, along with the folder it was in.
+ */
+#[derive(Default)]
+struct PreviewCollector {
+  size: u64,
+  mod_infos: Vec<(PathBuf, Vec<u8>)>,
+  current: Option<(PathBuf, Vec<u8>)>,
+}
+
+impl EntryVisitor for PreviewCollector {
+  fn start(&mut self, header: EntryHeader) -> Result<(), InstallError> {
+    self.current = None;
+    if header.kind != EntryKind::File {
+      return Ok(())
+    }
+
+    self.size += header.size;
+    let relative: PathBuf = Path::new(&header.name).components()
+      .filter(|component| !matches!(component, Component::CurDir))
+      .collect();
+    if relative.file_name().map_or(false, |file_name| file_name == "mod_info.json") {
+      self.current = Some((relative.parent().map(Path::to_path_buf).unwrap_or_default(), vec![]));
+    }
+
+    Ok(())
+  }
+
+  fn data(&mut self, chunk: &[u8]) -> Result<(), InstallError> {
+    if let Some((_, data)) = self.current.as_mut() {
+      if data.len() + chunk.len() <= MAX_PREVIEW_MOD_INFO {
+        data.extend_from_slice(chunk);
+      }
+    }
+
+    Ok(())
+  }
+
+  fn end(&mut self) -> Result<(), InstallError> {
+    if let Some(found) = self.current.take() {
+      self.mod_infos.push(found);
+    }
+
+    Ok(())
+  }
+}
+
+fn preview_folder(path: &PathBuf) -> Result<ArchivePreview, InstallError> {
+  let mods = find_nested_mods(path).context(Io {})?
+    .into_iter()
+    .filter_map(|mod_path| {
+      let mod_info = ModEntry::from_file(&mod_path).ok()?;
+      let folder = mod_path.strip_prefix(path).map(Path::to_path_buf).unwrap_or_default();
+
+      Some(PreviewedMod { folder, mod_info, replaces: None })
+    })
+    .collect();
+
+  Ok(ArchivePreview { mods, size: dir_size(path).context(Io {})? })
+}
+
+pub fn find_nested_mod(dest: &PathBuf) -> std::io::Result<Option<PathBuf>> {
+  Ok(find_nested_mods(dest)?.into_iter().next())
+}
+
+/**
+ * Every folder at or below `dest` containing a mod_info.json, in name order. Folders inside a mod aren't searched, as they
+ * belong to that mod.
+ */
+pub fn find_nested_mods(dest: &PathBuf) -> std::io::Result<Vec<PathBuf>> {
+  if dest.join("mod_info.json").is_file() {
+    return Ok(vec![dest.to_path_buf()])
+  }
+
+  let mut dirs = vec![];
+  for entry in read_dir(dest)? {
+    let entry = entry?;
+    if entry.file_type()?.is_dir() {
+      dirs.push(entry.path());
+    }
+  }
+  dirs.sort();
+
+  let mut mods = vec![];
+  for dir in dirs {
+    mods.extend(find_nested_mods(&dir)?);
+  }
+
+  Ok(mods)
+}
+
+/**
+ * Copies `source` into a temporary folder beside `destination`. Since that folder is on the same drive as the destination, the
+ * staged copy can then be put in place with a single rename. The temporary folder is removed when dropped, taking anything left
+ * in it with it, so only mods that were extracted into a temporary folder of their own are moved rather than copied.
+ */
+async fn stage_beside(source: &HybridPath, destination: &PathBuf, progress: &ProgressReporter) -> Result<(TempDir, PathBuf), InstallError> {
+  let parent = destination.parent().context(Any { detail: "Mod destination has no parent folder" })?.to_path_buf();
+  let staging = task::spawn_blocking(move || tempfile::Builder::new().prefix(".ssmm-staging-").tempdir_in(parent)).await
+    .expect("Create staging dir")
+    .context(Io {})?;
+  let staged = staging.path().join("new");
+  let disposable = matches!(source, HybridPath::Temp(..));
+  let source = source.get_path_copy();
+
+  if !disposable || rename(&source, &staged).await.is_err() {
+    let to = staged.clone();
+    let copy_progress = progress.clone();
+    task::spawn_blocking(move || {
+      let progress = copy_progress;
+      let total = dir_size(&source)?;
+      let mut copied = 0;
+      progress.report(InstallProgress::Copying { copied, total });
+
+      copy_dir_recursive(&to, &source, &mut |bytes| {
+        let reported = copied / REPORT_BYTES;
+        copied += bytes;
+        if copied / REPORT_BYTES != reported || copied == total {
+          progress.report(InstallProgress::Copying { copied, total });
+        }
+
+        !progress.is_cancelled()
+      })
+    }).await
+      .expect("Run blocking dir copy")
+      .context(Io {})
+      .map_err(|err| if progress.is_cancelled() { InstallError::Cancelled } else { err })?;
+  }
+
+  Ok((staging, staged))
+}
+
+/**
+ * `on_copied` is called with the size of each file once it has been copied, and stops the copy by returning false.
+ */
+fn copy_dir_recursive(to: &PathBuf, from: &PathBuf, on_copied: &mut dyn FnMut(u64) -> bool) -> io::Result<()> {
+  if !to.exists() {
+    create_dir_all(to)?;
+  }
+
+  for entry in from.read_dir()? {
+    let entry = entry?;
+    if entry.file_type()?.is_dir() {
+      copy_dir_recursive(&to.to_path_buf().join(entry.file_name()), &entry.path(), on_copied)?;
+    } else if entry.file_type()?.is_file() {
+      if !on_copied(copy(entry.path(), &to.to_path_buf().join(entry.file_name()))?) {
+        return Err(io::Error::new(io::ErrorKind::Interrupted, "Copy stopped"));
+      }
+    }
+  }
+
+  Ok(())
+}
+
+fn dir_size(dir: &PathBuf) -> io::Result<u64> {
+  let mut size = 0;
+  for entry in dir.read_dir()? {
+    let entry = entry?;
+    if entry.file_type()?.is_dir() {
+      size += dir_size(&entry.path())?;
+    } else if entry.file_type()?.is_file() {
+      size += entry.metadata()?.len();
+    }
+  }
+
+  Ok(size)
+}
+
+/**
+ * Installs the mod found at `source` as `destination`, which must not exist yet.
+ * Nothing appears at `destination` unless the whole mod was copied successfully.
+ */
+pub async fn install_to(source: HybridPath, destination: PathBuf, progress: ProgressReporter) -> Result<(), InstallError> {
+  let exists = |destination: &PathBuf| InstallError::Any { detail: format!("{} already exists", destination.display()) };
+  if destination.exists() {
+    return Err(exists(&destination))
+  }
+
+  let (_staging, staged) = stage_beside(&source, &destination, &progress).await?;
+  progress.check_cancelled()?;
+
+  // Checked again, as something may have been installed there while staging
+  if destination.exists() {
+    return Err(exists(&destination))
+  }
+
+  rename(&staged, &destination).await.context(Io {})
+}
+
+/**
+ * Replaces the mod installed at `old_path` with the mod found at `new_path`.
+ * The new mod is staged beside the old one and swapped in with renames, and the old mod is moved into the trash rather than deleted,
+ * so it can be restored if the new one turns out to be broken. If any step fails the old mod is put back as it was.
+ */
+pub async fn replace(new_path: HybridPath, old_path: PathBuf, progress: ProgressReporter) -> Result<(), InstallError> {
+  let (staging, staged) = stage_beside(&new_path, &old_path, &progress).await?;
+  let previous = staging.path().join("previous");
+  progress.check_cancelled()?;
+
+  rename(&old_path, &previous).await.context(Io {})?;
+
+  if let Err(err) = rename(&staged, &old_path).await {
+    return Err(restore_previous(staging, &old_path, InstallError::Io { source: err }).await)
+  }
+
+  match trash::move_to_trash_as(previous.clone(), old_path.clone(), RemovalReason::Replaced).await {
+    // Anything left behind is inside the staging folder, which is deleted on the way out
+    Ok(_) | Err(InstallError::LeftBehind { .. }) => Ok(()),
+    Err(err) => {
+      if rename(&old_path, &staged).await.is_err() {
+        let kept = staging.into_path().join("previous");
+
+        return Err(InstallError::Any { detail: format!("{}. The previous version could not be put back and has been left at {}", err, kept.display()) })
+      }
+
+      Err(restore_previous(staging, &old_path, err).await)
+    }
+  }
+}
+
+/**
+ * Moves the previous version of a mod back out of the staging folder after a failed replace. If even that fails the staging folder
+ * is kept, since it holds the only copy of the previous version.
+ */
+async fn restore_previous(staging: TempDir, old_path: &PathBuf, err: InstallError) -> InstallError {
+  match rename(staging.path().join("previous"), old_path).await {
+    Ok(()) => err,
+    Err(_) => {
+      let kept = staging.into_path().join("previous");
+
+      InstallError::Any { detail: format!("{}. The previous version could not be put back and has been left at {}", err, kept.display()) }
+    }
+  }
+}
+
+/**
+ * Moves an installed mod's folder into `move_to` if given, or into the trash otherwise.
+ */
+pub async fn uninstall(path: PathBuf, move_to: Option<PathBuf>) -> Result<(), InstallError> {
+  match move_to {
+    Some(move_to) => {
+      let destination = move_to.join(path.file_name().context(Any { detail: "Mod folder has no name" })?);
+      if destination.exists() {
+        return Err(InstallError::Any { detail: format!("{} already exists", destination.display()) })
+      }
+
+      relocate(path, destination).await
+    },
+    None => trash::move_to_trash(path, RemovalReason::Uninstalled).await.map(|_| ())
+  }
+}
+
+/**
+ * Moves a folder, falling back to copying it and deleting the original when it can't simply be renamed, e.g. across drives.
+ * The original is only deleted once the copy is complete, and a partial copy is cleaned up if copying fails. If the copy is
+ * complete but the original can't be deleted, fails with `InstallError::LeftBehind`, leaving the copy in place.
+ */
+pub async fn relocate(from: PathBuf, to: PathBuf) -> Result<(), InstallError> {
+  if rename(&from, &to).await.is_ok() {
+    return Ok(())
+  }
+
+  let (source, destination) = (from.clone(), to.clone());
+  let copied = task::spawn_blocking(move || copy_dir_recursive(&destination, &source, &mut |_| true)).await
+    .expect("Run blocking dir copy");
+  if let Err(err) = copied {
+    let _ = task::spawn_blocking(move || remove_dir_all(to)).await;
+
+    return Err(err).context(Io {})
+  }
+
+  let original = from.clone();
+  task::spawn_blocking(move || remove_dir_all(original)).await
+    .expect("Run blocking dir removal")
+    .map_err(|source| InstallError::LeftBehind { path: from, source })
+}
+
+/**
+ * Downloads an update, checks that it is the archive and version we expect and, if so, replaces the mod at `old_path` with it.
+ * An archive of the update already in the archive cache is used instead of downloading it again, and a downloaded one is
+ * added to the cache once the update succeeds. Returns the name of the updated mod.
+ */
+pub async fn auto_update(
+  url: String,
+  target_version: String,
+  old_path: PathBuf,
+  checksum: ArchiveChecksum,
+  options: DownloadOptions,
+  progress: ProgressReporter
+) -> Result<String, InstallError> {
+  let installed_id = ModEntry::from_file(&old_path).ok().map(|installed| installed.id);
+  let cached = match &installed_id {
+    Some(id) => archive_cache::get(id, &target_version).await,
+    None => None
+  };
+  let from_cache = match cached {
+    Some(cached) => {
+      let prepared = match checksum.verify(cached.path.clone()).await {
+        Ok(()) => stage_update(cached.path.clone(), installed_id.as_deref(), &target_version, progress.clone()).await,
+        Err(err) => Err(err)
+      };
+
+      match prepared {
+        Ok(prepared) => Some(prepared),
+        Err(InstallError::Cancelled) => return Err(InstallError::Cancelled),
+        Err(err) => {
+          // A bad cached archive shouldn't stop the update, it just has to be downloaded again
+          dbg!(format!("Discarding cached archive of {} {}: {}", cached.id, cached.version, err));
+          let _ = archive_cache::remove(&cached.id, &cached.version).await;
+
+          None
+        }
+      }
+    },
+    None => None
+  };
+
+  let (hybrid, mod_info, downloaded) = match from_cache {
+    Some((hybrid, mod_info)) => (hybrid, mod_info, None),
+    None => {
+      let file = download(url.clone(), &checksum, options, progress.clone()).await?;
+      let (hybrid, mod_info) = stage_update(file.path().to_path_buf(), installed_id.as_deref(), &target_version, progress.clone()).await?;
+
+      (hybrid, mod_info, Some(file))
+    }
+  };
+
+  replace(hybrid, old_path, progress).await?;
+
+  if let Some(file) = downloaded {
+    if let Err(err) = archive_cache::insert(&mod_info.id, &target_version, &url, file.path(), options.cache_limit).await {
+      dbg!(format!("Failed to cache archive of {} {}: {}", mod_info.id, target_version, err));
+    }
+  }
+
+  Ok(mod_info.name)
+}
+
+/**
+ * Stages the archive of an update, making sure it actually contains the expected version. If the archive holds several mods,
+ * the one with the same ID as the installed mod is used, and the update fails if there isn't one.
+ */
+async fn stage_update(archive: PathBuf, id: Option<&str>, target_version: &str, progress: ProgressReporter) -> Result<(HybridPath, ModEntry), InstallError> {
+  let mut mods = stage_all(archive, progress).await?;
+  let idx = match mods.iter().position(|(_, mod_info)| Some(mod_info.id.as_str()) == id) {
+    Some(idx) => idx,
+    None if mods.len() == 1 => 0,
+    None => return Err(InstallError::Any {
+      detail: format!("Downloaded archive holds {} mods, but none of them is {}", mods.len(), id.unwrap_or("the installed mod"))
+    })
+  };
+  let (hybrid, mod_info) = mods.remove(idx);
+
+  if mod_info.version_checker.as_ref().map(|v| v.version.to_string()).as_deref() != Some(target_version) {
+    return Err(InstallError::Any { detail: format!("Downloaded version does not match expected version") })
+  }
+
+  Ok((hybrid, mod_info))
+}
+
+/**
+ * What a downloaded archive is expected to be, as published alongside its download link. Missing parts aren't checked.
+ */
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ArchiveChecksum {
+  /**
+   * Hex encoded SHA-256 digest of the archive. Case doesn't matter.
+   */
+  #[serde(default)]
+  pub sha256: Option<String>,
+  #[serde(default)]
+  pub size: Option<u64>,
+}
+
+impl From<&ModVersionMeta> for ArchiveChecksum {
+  fn from(meta: &ModVersionMeta) -> Self {
+    ArchiveChecksum {
+      sha256: meta.direct_download_sha256.clone(),
+      size: meta.direct_download_size,
+    }
+  }
+}
+
+impl ArchiveChecksum {
+  pub fn is_empty(&self) -> bool {
+    self.sha256.is_none() && self.size.is_none()
+  }
+
+  /**
+   * Checks the file at `path` against whichever of the size and digest are known, failing with `ChecksumMismatch` if either differs.
+   */
+  pub async fn verify(&self, path: PathBuf) -> Result<(), InstallError> {
+    if self.is_empty() {
+      return Ok(())
+    }
+
+    let checksum = self.clone();
+    task::spawn_blocking(move || checksum.verify_blocking(&path)).await.expect("Run checksum verification")
+  }
+
+  fn verify_blocking(&self, path: &Path) -> Result<(), InstallError> {
+    if let Some(size) = self.size {
+      let actual = std::fs::metadata(path).context(Io {})?.len();
+      if actual != size {
+        return Err(InstallError::ChecksumMismatch { detail: format!("expected {} bytes, got {}", size, actual) })
+      }
+    }
+
+    if let Some(sha256) = &self.sha256 {
+      let mut file = std::fs::File::open(path).context(Io {})?;
+      let mut context = digest::Context::new(&digest::SHA256);
+      let mut buf = vec![0; 64 * 1024];
+      loop {
+        let read = io::Read::read(&mut file, &mut buf).context(Io {})?;
+        if read == 0 {
+          break;
+        }
+        context.update(&buf[..read]);
+      }
+      let actual: String = context.finish().as_ref().iter().map(|byte| format!("{:02x}", byte)).collect();
+
+      if !actual.eq_ignore_ascii_case(sha256.trim()) {
+        return Err(InstallError::ChecksumMismatch { detail: format!("expected SHA-256 {}, got {}", sha256.trim(), actual) })
+      }
+    }
+
+    Ok(())
+  }
+}
+
+/**
+ * How hard `download` tries before giving up on a flaky connection.
+ */
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct DownloadOptions {
+  /**
+   * How many times a failed download is retried. Retries resume from where the last attempt stopped if the server allows it.
+   */
+  pub retries: u32,
+  /**
+   * How long to wait for a connection, or for more data on an open one, before the attempt counts as failed.
+   */
+  pub timeout: Duration,
+  /**
+   * How many bytes of downloaded archives the archive cache can hold. 0 disables caching.
+   */
+  pub cache_limit: u64,
+}
+
+impl Default for DownloadOptions {
+  fn default() -> Self {
+    DownloadOptions {
+      retries: 5,
+      timeout: Duration::from_secs(30),
+      cache_limit: 2048 * 1024 * 1024,
+    }
+  }
+}
+
+impl DownloadOptions {
+  pub fn from_config(config: &Config) -> Self {
+    DownloadOptions {
+      retries: config.download_retries,
+      timeout: Duration::from_secs(config.download_timeout_secs.max(1)),
+      cache_limit: config.archive_cache_mb.saturating_mul(1024 * 1024),
+    }
+  }
+}
+
+/**
+ * A finished download. The file is deleted once this is dropped.
+ */
+#[derive(Debug)]
+pub struct DownloadedFile {
+  path: PathBuf,
+  /**
+   * Keeps the URL claimed until the file is done with, so another download of it can't finish over this one.
+   */
+  _partial: PartialDownload,
+}
+
+impl DownloadedFile {
+  pub fn path(&self) -> &Path {
+    &self.path
+  }
+}
+
+impl Drop for DownloadedFile {
+  fn drop(&mut self) {
+    let _ = std::fs::remove_file(&self.path);
+  }
+}
+
+/**
+ * Partial downloads that haven't been touched for this long are assumed to be abandoned, and deleted by `prune_partials`.
+ */
+pub const STALE_PARTIAL_AGE: Duration = Duration::from_secs(7 * 24 * 60 * 60);
+
+lazy_static! {
+  /**
+   * Partial files currently being written to, so two downloads of the same URL don't write over each other.
+   */
+  static ref ACTIVE_DOWNLOADS: Mutex<HashSet<PathBuf>> = Mutex::new(HashSet::new());
+}
+
+async fn downloads_dir(try_make: bool) -> PathBuf {
+  config_dir(try_make).await.join("downloads")
+}
+
+/**
+ * A partial download and the validator (ETag or Last-Modified) of the response it came from, which makes sure a resumed
+ * download is still of the same file. Claimed for the lifetime of the value.
+ */
+#[derive(Debug)]
+struct PartialDownload {
+  path: PathBuf,
+  validator_path: PathBuf,
+}
+
+impl PartialDownload {
+  /**
+   * Claims the partial for `url`, which is refused if another download of the same URL is still going.
+   */
+  async fn claim(url: &str) -> Result<Self, InstallError> {
+    let dir = downloads_dir(true).await;
+    tokio::fs::create_dir_all(&dir).await.context(Io {})?;
+
+    // Hashed with SHA-256 rather than the standard library's hasher, as that isn't guaranteed to be stable between builds
+    let key: String = digest::digest(&digest::SHA256, url.as_bytes()).as_ref().iter().map(|byte| format!("{:02x}", byte)).collect();
+
+    let path = dir.join(format!("{}.part", key));
+    if !ACTIVE_DOWNLOADS.lock().expect("Lock active downloads").insert(path.clone()) {
+      return Err(InstallError::Any { detail: format!("{} is already being downloaded", url) })
+    }
+
+    Ok(PartialDownload {
+      validator_path: path.with_extension("validator"),
+      path,
+    })
+  }
+
+  fn len(&self) -> u64 {
+    std::fs::metadata(&self.path).map(|meta| meta.len()).unwrap_or(0)
+  }
+
+  fn validator(&self) -> Option<String> {
+    std::fs::read_to_string(&self.validator_path).ok().filter(|validator| !validator.is_empty())
+  }
+
+  /**
+   * Empties the partial file, remembering the validator of the response that is about to be written into it.
+   */
+  fn restart(&self, validator: Option<String>) -> Result<std::fs::File, InstallError> {
+    match validator {
+      Some(validator) => std::fs::write(&self.validator_path, validator).context(Io {})?,
+      None => {
+        let _ = std::fs::remove_file(&self.validator_path);
+      }
+    }
+
+    std::fs::File::create(&self.path).context(Io {})
+  }
+
+  fn discard(&self) {
+    let _ = std::fs::remove_file(&self.path);
+    let _ = std::fs::remove_file(&self.validator_path);
+  }
+
+  /**
+   * Hands the completed file over to the caller. The URL stays claimed until the caller drops it.
+   */
+  fn finish(self) -> Result<DownloadedFile, InstallError> {
+    let path = self.path.with_extension("download");
+    std::fs::rename(&self.path, &path).context(Io {})?;
+    let _ = std::fs::remove_file(&self.validator_path);
+
+    Ok(DownloadedFile { path, _partial: self })
+  }
+}
+
+impl Drop for PartialDownload {
+  fn drop(&mut self) {
+    if let Ok(mut active) = ACTIVE_DOWNLOADS.lock() {
+      active.remove(&self.path);
+    }
+  }
+}
+
+/**
+ * Deletes partial downloads, and their validators, that no download is using and that haven't been written to for
+ * `older_than`. Returns the number of partial downloads deleted.
+ */
+pub async fn prune_partials(older_than: Duration) -> usize {
+  let dir = downloads_dir(false).await;
+
+  task::spawn_blocking(move || {
+    let entries = match read_dir(&dir) {
+      Ok(entries) => entries,
+      Err(_) => return 0
+    };
+    // Held throughout, so a download can't claim a partial while it's being deleted
+    let active = ACTIVE_DOWNLOADS.lock().expect("Lock active downloads");
+
+    let mut pruned = 0;
+    for path in entries.filter_map(|entry| entry.ok().map(|entry| entry.path())) {
+      let partial = match path.extension().and_then(|ext| ext.to_str()) {
+        Some("part") => path.clone(),
+        Some("validator") => path.with_extension("part"),
+        _ => continue
+      };
+      if active.contains(&partial) {
+        continue;
+      }
+
+      // A validator is only stale along with its partial, unless the partial is already gone
+      let modified = std::fs::metadata(&partial)
+        .or_else(|_| std::fs::metadata(&path))
+        .and_then(|meta| meta.modified());
+      let stale = matches!(modified.map(|modified| modified.elapsed()), Ok(Ok(age)) if age >= older_than);
+      if stale && std::fs::remove_file(&path).is_ok() && path == partial {
+        pruned += 1;
+      }
+    }
+
+    pruned
+  }).await.unwrap_or_default()
+}
+
+/**
+ * Downloads the file at `url`, retrying with exponential backoff when the connection drops, times out or the server has a
+ * temporary error. Progress is kept in a partial file in the manager's config folder, and resumed with an HTTP Range request
+ * where the server supports it, including by a later call if every retry fails. Cancelling discards the partial file, and
+ * partial files abandoned for `STALE_PARTIAL_AGE` are deleted. Only one download of a URL can run at a time.
+ * The finished file is checked against `checksum`, and deleted if it doesn't match.
+ */
+pub async fn download(url: String, checksum: &ArchiveChecksum, options: DownloadOptions, progress: ProgressReporter) -> Result<DownloadedFile, InstallError> {
+  let client = reqwest::Client::builder()
+    .connect_timeout(options.timeout)
+    .build()
+    .context(Network {})?;
+  let partial = PartialDownload::claim(&url).await?;
+  prune_partials(STALE_PARTIAL_AGE).await;
+
+  let mut attempt = 0;
+  loop {
+    let res = match progress.check_cancelled() {
+      Ok(()) => download_attempt(&client, &url, &partial, options.timeout, &progress).await,
+      Err(err) => Err(err)
+    };
+
+    match res {
+      Ok(()) => {
+        let file = partial.finish()?;
+        checksum.verify(file.path().to_path_buf()).await?;
+
+        return Ok(file)
+      },
+      Err(InstallError::Cancelled) => {
+        partial.discard();
+
+        return Err(InstallError::Cancelled)
+      },
+      Err(err) if attempt < options.retries && is_retryable(&err) => {
+        attempt += 1;
+        let backoff = Duration::from_secs((1u64 << (attempt - 1).min(5)).min(30));
+        dbg!(format!("Download attempt {} of {} failed, retrying in {:?}: {}", attempt, options.retries + 1, backoff, err));
+
+        // Sleep in short steps so a cancellation doesn't have to wait out the backoff
+        let mut waited = Duration::from_secs(0);
+        while waited < backoff {
+          if progress.is_cancelled() {
+            break;
+          }
+          tokio::time::sleep(Duration::from_millis(250)).await;
+          waited += Duration::from_millis(250);
+        }
+      },
+      Err(err) => return Err(err)
+    }
+  }
+}
+
+/**
+ * Dropped connections, timeouts and 5xx/429 responses might go away on their own. Anything else, such as an invalid URL or a
+ * redirect loop, won't.
+ */
+fn is_retryable(err: &InstallError) -> bool {
+  match err {
+    InstallError::Network { source } => match source.status() {
+      Some(status) => status.is_server_error() || status == StatusCode::TOO_MANY_REQUESTS,
+      None => source.is_timeout() || source.is_connect() || source.is_body()
+    },
+    InstallError::Interrupted { .. } => true,
+    _ => false
+  }
+}
+
+/**
+ * Makes one request for whatever of the file the partial doesn't have yet, appending to it as data arrives.
+ */
+async fn download_attempt(client: &reqwest::Client, url: &str, partial: &PartialDownload, timeout: Duration, progress: &ProgressReporter) -> Result<(), InstallError> {
+  let existing = partial.len();
+  let validator = partial.validator();
+  let resuming = existing > 0 && validator.is_some();
+
+  let mut req = client.get(url);
+  if let (true, Some(validator)) = (resuming, &validator) {
+    // If-Range makes the server send the whole file instead if it has changed since the partial was started
+    req = req.header(header::RANGE, format!("bytes={}-", existing))
+      .header(header::IF_RANGE, validator.as_str());
+  }
+  let res = tokio::time::timeout(timeout, req.send()).await
+    .map_err(|_| InstallError::Interrupted { detail: format!("No response for {} seconds", timeout.as_secs()) })?
+    .context(Network {})?;
+
+  if res.status() == StatusCode::RANGE_NOT_SATISFIABLE {
+    // The partial is at least as long as the file now on the server, so it can't be trusted
+    partial.discard();
+
+    return Err(InstallError::Interrupted { detail: String::from("Server rejected resuming the download") })
+  }
+  let res_validator = res.headers().get(header::ETAG)
+    .and_then(|etag| etag.to_str().ok())
+    .filter(|etag| !etag.starts_with("W/"))
+    .or_else(|| res.headers().get(header::LAST_MODIFIED).and_then(|modified| modified.to_str().ok()))
+    .map(String::from);
+  let range_start = res.headers().get(header::CONTENT_RANGE)
+    .and_then(|range| range.to_str().ok())
+    .and_then(|range| range.strip_prefix("bytes "))
+    .and_then(|range| range.split('-').next())
+    .and_then(|start| start.trim().parse::<u64>().ok());
+  let mut res = res.error_for_status().context(Network {})?;
+
+  let (mut file, mut received) = if res.status() == StatusCode::PARTIAL_CONTENT {
+    if !resuming || range_start != Some(existing) {
+      partial.discard();
+
+      return Err(InstallError::Interrupted { detail: String::from("Server sent a different part of the file than was asked for") })
+    }
+
+    (std::fs::OpenOptions::new().append(true).open(&partial.path).context(Io {})?, existing)
+  } else {
+    (partial.restart(res_validator)?, 0)
+  };
+  let total = res.content_length().map(|remaining| remaining + received);
+
+  progress.report(InstallProgress::Downloading { received, total });
+  while let Some(chunk) = tokio::time::timeout(timeout, res.chunk()).await
+    .map_err(|_| InstallError::Interrupted { detail: format!("No data received for {} seconds", timeout.as_secs()) })?
+    .context(Network {})?
+  {
+    progress.check_cancelled()?;
+    file.write_all(&chunk).context(Io {})?;
+
+    let reported = received / REPORT_BYTES;
+    received += chunk.len() as u64;
+    if received / REPORT_BYTES != reported {
+      progress.report(InstallProgress::Downloading { received, total });
+    }
+  };
+  file.flush().context(Io {})?;
+  progress.report(InstallProgress::Downloading { received, total });
+
+  match total {
+    Some(total) if received < total => Err(InstallError::Interrupted { detail: format!("Connection closed after {} of {} bytes", received, total) }),
+    _ => Ok(())
+  }
+}
+
+#[derive(Debug, Clone)]
+pub enum HybridPath {
+  PathBuf(PathBuf),
+  Temp(Arc<TempDir>, Option<PathBuf>),
+}
+
+impl HybridPath {
+  pub fn get_path_copy(&self) -> PathBuf {
+    match self {
+      HybridPath::PathBuf(ref path) => path.clone(),
+      HybridPath::Temp(_, Some(ref path)) => path.clone(),
+      HybridPath::Temp(ref arc, None) => arc.path().to_path_buf()
+    }
+  }
+}
+
+#[derive(Debug, Snafu)]
+pub enum InstallError {
+  Io { source: std::io::Error },
+  CompressTools { source: compress_tools::Error },
+  Zip { source: zip::result::ZipError },
+  #[snafu(display("{}", detail))]
+  UnsupportedArchive { detail: String },
+  #[snafu(display("Refused to extract archive, {}", detail))]
+  UnsafeArchive { detail: String },
+  Network { source: reqwest::Error },
+  #[snafu(display("Download interrupted: {}", detail))]
+  Interrupted { detail: String },
+  #[snafu(display("Downloaded archive failed verification, {}", detail))]
+  ChecksumMismatch { detail: String },
+  #[snafu(display("Cancelled"))]
+  Cancelled,
+  #[snafu(display("The mod folder was copied, but the original could not be removed and is still at {}: {}", path.display(), source))]
+  LeftBehind { path: PathBuf, source: std::io::Error },
+  #[snafu(display("Updates to `{}` are held", id))]
+  UpdateHeld { id: String },
+  #[snafu(display("`{}` has no update available", id))]
+  NoUpdate { id: String },
+  #[snafu(display("`{}` does not support auto-update", id))]
+  ManualUpdate { id: String },
+  #[snafu(display("{}", detail))]
+  Any { detail: String }
+}
@@ -0,0 +1,49 @@
+#![recursion_limit="1000"]
+#![feature(result_flattening)]
+
+use serde::{Serialize, Deserialize};
+
+// https://users.rust-lang.org/t/show-value-only-in-debug-mode/43686/5
+macro_rules! dbg {
+  ($($x:tt)*) => {
+    {
+      #[cfg(debug_assertions)]
+      {
+        std::dbg!($($x)*)
+      }
+      #[cfg(not(debug_assertions))]
+      {
+        ($($x)*)
+      }
+    }
+  }
+}
+
+pub mod mods;
+pub mod installer;
+pub mod vmparams;
+pub mod repository;
+pub mod util;
+pub mod config;
+pub mod profiles;
+pub mod conflicts;
+pub mod trash;
+pub mod archive;
+pub mod archive_cache;
+pub mod version_cache;
+
+pub use repository::{GameInstall, ModRepository};
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub enum LoadError {
+  NoSuchFile,
+  ReadError,
+  FormatError
+}
+
+#[derive(Debug, Clone)]
+pub enum SaveError {
+  FileError,
+  WriteError,
+  FormatError,
+}
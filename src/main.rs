@@ -1,7 +1,6 @@
 #![cfg_attr(not(debug_assertions), windows_subsystem = "windows")]
 #![recursion_limit="1000"]
 #![feature(option_zip)]
-#![feature(result_flattening)]
 #![feature(async_closure)]
 
 use iced::{Application, Settings};
@@ -0,0 +1,774 @@
+use std::{
+  io::Read,
+  path::{Path, PathBuf},
+  fmt::Display
+};
+use serde::{Serialize, Deserialize};
+use json_comments::strip_comments;
+use json5;
+use handwritten_json;
+use if_chain::if_chain;
+use lazy_static::lazy_static;
+use regex::Regex;
+
+lazy_static! {
+  static ref VERSION_REGEX: Regex = Regex::new(r"\.|a-RC|A-RC|a-rc|a").unwrap();
+}
+
+use serde_aux::prelude::*;
+
+use crate::{LoadError, SaveError};
+use crate::util::{self, parse_csv};
+
+#[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord)]
+pub enum UpdateStatus {
+  Error,
+  Major(Version),
+  Minor(Version),
+  Patch(Version),
+  UpToDate,
+  Discrepancy(Version),
+  /**
+   * A newer version is available, but the user has asked not to be offered it.
+   */
+  Held(Version),
+}
+
+impl UpdateStatus {
+  /**
+   * Whether the remote version is newer than the installed one.
+   */
+  pub fn is_update_available(&self) -> bool {
+    matches!(self, UpdateStatus::Major(_) | UpdateStatus::Minor(_) | UpdateStatus::Patch(_))
+  }
+}
+
+impl Display for UpdateStatus {
+  fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::result::Result<(), std::fmt::Error> {
+    match self {
+      UpdateStatus::Major(_) => write!(f, "Major"),
+      UpdateStatus::Minor(_) => write!(f, "Minor"),
+      UpdateStatus::Patch(_) => write!(f, "Patch"),
+      UpdateStatus::UpToDate => write!(f, "Up to date"),
+      UpdateStatus::Error => write!(f, "Error"),
+      UpdateStatus::Discrepancy(_) => write!(f, "Discrepancy"),
+      UpdateStatus::Held(_) => write!(f, "Held"),
+    }
+  }
+}
+
+/**
+ * Stops updates to a mod being offered, such as to keep a playthrough's save loading.
+ */
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub enum UpdateHold {
+  /**
+   * No update is offered, whatever its version.
+   */
+  All,
+  /**
+   * Only the given version is skipped, so anything newer is offered again.
+   */
+  Version(String),
+}
+
+impl UpdateHold {
+  pub fn holds(&self, version: &Version) -> bool {
+    match self {
+      UpdateHold::All => true,
+      UpdateHold::Version(held) => *held == version.to_string(),
+    }
+  }
+}
+
+impl Display for UpdateHold {
+  fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::result::Result<(), std::fmt::Error> {
+    match self {
+      UpdateHold::All => write!(f, "Ignoring all updates"),
+      UpdateHold::Version(version) => write!(f, "Ignoring version {}", version),
+    }
+  }
+}
+
+#[derive(Debug, Clone, Deserialize)]
+#[serde(untagged)]
+pub enum VersionUnion {
+  String(String),
+  Object(Version)
+}
+
+impl Display for VersionUnion {
+  fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::result::Result<(), std::fmt::Error> {
+    let output: String = match self {
+      VersionUnion::String(s) => s.to_string(),
+      VersionUnion::Object(o) => o.to_string()
+    };
+    write!(f, "{}", output)
+  }
+}
+
+impl From<VersionUnion> for String {
+  fn from(version_union: VersionUnion) -> Self {
+    version_union.to_string()
+  }
+}
+
+impl VersionUnion {
+  fn components(&self) -> Vec<String> {
+    match self {
+      VersionUnion::String(s) => s.trim_start_matches(|c| c == 'v' || c == 'V')
+        .split(|c: char| !c.is_alphanumeric())
+        .filter(|c| !c.is_empty())
+        .map(|c| c.to_string())
+        .collect(),
+      VersionUnion::Object(o) => vec![o.major.to_string(), o.minor.to_string(), o.patch.clone()]
+        .into_iter()
+        .filter(|c| !c.is_empty())
+        .collect()
+    }
+  }
+
+  /**
+   * Compares component by component, numerically where both sides are numbers and lexically otherwise.
+   * Components the installed version doesn't have are treated as 0, so `1.2` satisfies a requirement of `1.2.0`.
+   */
+  pub fn is_at_least(&self, required: &VersionUnion) -> bool {
+    use std::cmp::Ordering;
+
+    let have = self.components();
+    for (idx, need) in required.components().iter().enumerate() {
+      let have = have.get(idx).map(|c| c.as_str()).unwrap_or("0");
+      let ordering = match (have.parse::<u64>(), need.parse::<u64>()) {
+        (Ok(have), Ok(need)) => have.cmp(&need),
+        _ => have.cmp(need.as_str())
+      };
+
+      match ordering {
+        Ordering::Greater => return true,
+        Ordering::Less => return false,
+        Ordering::Equal => {}
+      }
+    }
+
+    true
+  }
+}
+
+/**
+ * An entry from the `dependencies` array in mod_info.json.
+ */
+#[derive(Debug, Clone, Deserialize)]
+pub struct Dependency {
+  pub id: String,
+  #[serde(default)]
+  pub name: Option<String>,
+  #[serde(default)]
+  pub version: Option<VersionUnion>,
+}
+
+/**
+ * Dependencies that can't be read, such as one without an ID, are skipped rather than failing the whole mod_info.json.
+ */
+fn deserialize_dependencies<'de, D: serde::Deserializer<'de>>(deserializer: D) -> Result<Vec<Dependency>, D::Error> {
+  let entries = match serde_json::Value::deserialize(deserializer)? {
+    serde_json::Value::Array(entries) => entries,
+    serde_json::Value::Null => vec![],
+    other => {
+      dbg!(format!("Skipping dependencies that aren't a list: {}", other));
+      vec![]
+    }
+  };
+
+  Ok(entries.into_iter()
+    .filter_map(|entry| match serde_json::from_value::<Dependency>(entry.clone()) {
+      Ok(dependency) => Some(dependency),
+      Err(err) => {
+        dbg!(format!("Skipping unreadable dependency {}: {}", entry, err));
+        None
+      }
+    })
+    .collect())
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum DependencyStatus {
+  Satisfied,
+  Missing,
+  Disabled,
+  TooOld(String),
+}
+
+impl Dependency {
+  pub fn status(&self, installed: Option<&ModEntry>) -> DependencyStatus {
+    match installed {
+      None => DependencyStatus::Missing,
+      Some(entry) if self.version.as_ref().map_or(false, |required| !entry.version.is_at_least(required)) => {
+        DependencyStatus::TooOld(entry.version.to_string())
+      },
+      Some(entry) if !entry.enabled => DependencyStatus::Disabled,
+      Some(_) => DependencyStatus::Satisfied
+    }
+  }
+}
+
+impl Display for Dependency {
+  fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::result::Result<(), std::fmt::Error> {
+    write!(f, "{}", self.name.as_ref().unwrap_or(&self.id))?;
+    if let Some(version) = &self.version {
+      write!(f, " {}+", version)?;
+    }
+
+    Ok(())
+  }
+}
+
+impl Display for DependencyStatus {
+  fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::result::Result<(), std::fmt::Error> {
+    match self {
+      DependencyStatus::Satisfied => write!(f, "OK"),
+      DependencyStatus::Missing => write!(f, "Not installed"),
+      DependencyStatus::Disabled => write!(f, "Disabled"),
+      DependencyStatus::TooOld(installed) => write!(f, "Too old, {} installed", installed),
+    }
+  }
+}
+
+#[derive(Debug, Clone, Deserialize)]
+pub struct ModEntry {
+  pub id: String,
+  pub name: String,
+  #[serde(default)]
+  pub author: String,
+  pub version: VersionUnion,
+  pub description: String,
+  #[serde(alias = "gameVersion")]
+  pub game_version: String,
+  #[serde(default)]
+  #[serde(deserialize_with = "deserialize_dependencies")]
+  pub dependencies: Vec<Dependency>,
+  #[serde(alias = "totalConversion")]
+  #[serde(default)]
+  #[serde(deserialize_with = "deserialize_bool_from_anything")]
+  pub total_conversion: bool,
+  #[serde(default)]
+  #[serde(deserialize_with = "deserialize_bool_from_anything")]
+  pub utility: bool,
+  #[serde(default)]
+  pub jars: Vec<String>,
+  #[serde(alias = "modPlugin")]
+  #[serde(default)]
+  pub mod_plugin: Option<String>,
+  #[serde(default)]
+  pub replace: Vec<String>,
+  #[serde(skip)]
+  pub parsed_game_version: (Option<String>, Option<String>, Option<String>, Option<String>),
+  #[serde(skip)]
+  pub enabled: bool,
+  #[serde(skip)]
+  pub version_checker: Option<ModVersionMeta>,
+  #[serde(skip)]
+  pub remote_version: Option<ModVersionMeta>,
+  #[serde(skip)]
+  pub update_status: Option<UpdateStatus>,
+  #[serde(skip)]
+  pub update_hold: Option<UpdateHold>,
+  /**
+   * When `remote_version` came from the version cache rather than a check this session, when it was fetched, in seconds
+   * since the Unix epoch.
+   */
+  #[serde(skip)]
+  pub remote_cached_at: Option<u64>,
+  /**
+   * Any Version Checker files listed after the first in version_files.csv.
+   */
+  #[serde(skip)]
+  pub bundled_versions: Vec<BundledVersion>,
+  /**
+   * The changelog of the available update, once fetched, or why it couldn't be.
+   */
+  #[serde(skip)]
+  pub changelog: Option<Result<String, String>>,
+  #[serde(skip)]
+  pub path: PathBuf,
+}
+
+/**
+ * A Version Checker file listed after the first in a mod's version_files.csv, usually for a library or sub-mod that comes
+ * bundled with it. It's checked alongside the mod's own, but its updates only come with the mod's or by hand.
+ */
+#[derive(Debug, Clone)]
+pub struct BundledVersion {
+  /**
+   * The `modName` of the local Version Checker file, as `local.id` is set to something unique to route check results back.
+   */
+  pub name: String,
+  pub local: ModVersionMeta,
+  pub remote: Option<ModVersionMeta>,
+  pub update_status: Option<UpdateStatus>,
+  /**
+   * When `remote` came from the version cache rather than a check this session, when it was fetched.
+   */
+  pub remote_cached_at: Option<u64>,
+}
+
+#[derive(Debug)]
+pub enum ModEntryError {
+  ParseError,
+  FileError
+}
+
+impl ModEntry {
+  pub fn from_file(path: &PathBuf) -> Result<ModEntry, ModEntryError> {
+    if let Ok(mod_info_file) = std::fs::read_to_string(path.join("mod_info.json")) {
+      if_chain! {
+        if let Ok(mut mod_info) = ModEntry::from_mod_info(&mod_info_file);
+        then {
+          let version_files = std::fs::read_to_string(path.join("data").join("config").join("version").join("version_files.csv"))
+            .map(|csv| parse_version_files(&csv))
+            .unwrap_or_default();
+          let mut versions = version_files.into_iter()
+            .filter_map(|version_filename| read_version_file(path, &version_filename).map(|version| (version_filename, version)));
+
+          mod_info.version_checker = versions.next().map(|(_, mut version)| {
+            version.id = mod_info.id.clone();
+            version
+          });
+          mod_info.bundled_versions = versions.map(|(version_filename, mut version)| BundledVersion {
+            name: std::mem::replace(&mut version.id, format!("{}/{}", mod_info.id, version_filename)),
+            local: version,
+            remote: None,
+            update_status: None,
+            remote_cached_at: None,
+          }).collect();
+          mod_info.path = path.clone();
+          Ok(mod_info)
+        } else {
+          Err(ModEntryError::ParseError)
+        }
+      }
+    } else {
+      Err(ModEntryError::FileError)
+    }
+  }
+
+  /**
+   * Parses the contents of a mod_info.json on their own, such as one read straight out of an archive.
+   * The result has no path or Version Checker file.
+   */
+  pub fn from_mod_info(mod_info_file: &str) -> Result<ModEntry, ModEntryError> {
+    let mut stripped = String::new();
+    strip_comments(mod_info_file.as_bytes()).read_to_string(&mut stripped).map_err(|_| ModEntryError::ParseError)?;
+
+    let mut mod_info = json5::from_str::<ModEntry>(&stripped).map_err(|_| ModEntryError::ParseError)?;
+    mod_info.parsed_game_version = parse_game_version(&mod_info.game_version);
+
+    Ok(mod_info)
+  }
+
+  /**
+   * Checks each declared dependency against the mod `lookup` finds for its ID.
+   */
+  pub fn dependency_statuses<'a>(&self, lookup: impl Fn(&str) -> Option<&'a ModEntry>) -> Vec<(Dependency, DependencyStatus)> {
+    self.dependencies.iter()
+      .map(|dependency| (dependency.clone(), dependency.status(lookup(&dependency.id))))
+      .collect()
+  }
+
+  pub fn depends_on(&self, id: &str) -> bool {
+    self.dependencies.iter().any(|dependency| dependency.id == id)
+  }
+
+  /**
+   * Whether this mod and `other` shouldn't be enabled together - a total conversion excludes every mod that isn't a utility mod
+   * or one of its dependencies.
+   */
+  pub fn excludes(&self, other: &ModEntry) -> bool {
+    self.id != other.id
+      && (self.total_conversion || other.total_conversion)
+      && !self.utility
+      && !other.utility
+      && !self.depends_on(&other.id)
+      && !other.depends_on(&self.id)
+  }
+
+  pub fn get_master_version(&self) -> Option<&ModVersionMeta> {
+    self.remote_version.as_ref()
+  }
+
+  /**
+   * Where to find the changelog of the available update, if there is one. The remote Version Checker file's link is
+   * preferred, as it's the one that knows about the update.
+   */
+  pub fn changelog_url(&self) -> Option<&String> {
+    if !self.update_status.as_ref().map_or(false, UpdateStatus::is_update_available) {
+      return None
+    }
+
+    self.remote_version.as_ref().and_then(|remote| remote.changelog_url.as_ref())
+      .or_else(|| self.version_checker.as_ref().and_then(|local| local.changelog_url.as_ref()))
+      .filter(|url| !url.is_empty())
+  }
+
+  /**
+   * The Version Checker files to check for this mod, its own first. Each is identified by its `id`, which is unique to it.
+   */
+  pub fn version_checks(&self) -> Vec<ModVersionMeta> {
+    self.version_checker.iter()
+      .chain(self.bundled_versions.iter().map(|bundled| &bundled.local))
+      .cloned()
+      .collect()
+  }
+
+  pub fn has_version_check(&self, check_id: &str) -> bool {
+    self.version_checks().iter().any(|version| version.id == check_id)
+  }
+
+  /**
+   * Whether an update is available for any of this mod's Version Checker files, including bundled ones.
+   */
+  pub fn any_update_available(&self) -> bool {
+    self.update_status.iter()
+      .chain(self.bundled_versions.iter().filter_map(|bundled| bundled.update_status.as_ref()))
+      .any(UpdateStatus::is_update_available)
+  }
+
+  /**
+   * Compares the result of a remote version check against the local Version Checker file it was for and records the outcome.
+   */
+  pub fn set_master_version(&mut self, check_id: &str, res: Result<ModVersionMeta, String>) {
+    let hold = self.update_hold.clone();
+
+    if self.version_checker.as_ref().map_or(false, |local| local.id == check_id) {
+      match res {
+        Ok(remote_version_meta) => {
+          self.remote_version = Some(remote_version_meta);
+          self.remote_cached_at = None;
+          self.refresh_update_status();
+        },
+        // A cached status is still more use than an error while offline, so it's kept, still marked as cached
+        Err(_err) if self.remote_cached_at.is_some() => {},
+        Err(_err) => {
+          self.update_status = Some(UpdateStatus::Error)
+        }
+      }
+    } else if let Some(bundled) = self.bundled_versions.iter_mut().find(|bundled| bundled.local.id == check_id) {
+      match res {
+        Ok(remote_version_meta) => {
+          bundled.update_status = Some(compare_versions(&bundled.local.version, &remote_version_meta.version, hold.as_ref()));
+          bundled.remote = Some(remote_version_meta);
+          bundled.remote_cached_at = None;
+        },
+        Err(_err) if bundled.remote_cached_at.is_some() => {},
+        Err(_err) => {
+          bundled.update_status = Some(UpdateStatus::Error)
+        }
+      }
+    } else {
+      dbg!("Have a remote version file, but the local version file is missing, which is odd to say the least.");
+    }
+  }
+
+  /**
+   * Shows the status from a remote version fetched in an earlier session, unless this session's check has already finished.
+   */
+  pub fn set_cached_master_version(&mut self, check_id: &str, remote_version_meta: ModVersionMeta, fetched_at: u64) {
+    let hold = self.update_hold.clone();
+
+    if self.version_checker.as_ref().map_or(false, |local| local.id == check_id) {
+      if self.update_status.is_none() {
+        self.remote_version = Some(remote_version_meta);
+        self.remote_cached_at = Some(fetched_at);
+        self.refresh_update_status();
+      }
+    } else if let Some(bundled) = self.bundled_versions.iter_mut().find(|bundled| bundled.local.id == check_id) {
+      if bundled.update_status.is_none() {
+        bundled.update_status = Some(compare_versions(&bundled.local.version, &remote_version_meta.version, hold.as_ref()));
+        bundled.remote = Some(remote_version_meta);
+        bundled.remote_cached_at = Some(fetched_at);
+      }
+    }
+  }
+
+  /**
+   * Sets or clears the hold on this mod's updates, updating its status to match if its remote version is known.
+   */
+  pub fn set_update_hold(&mut self, hold: Option<UpdateHold>) {
+    self.update_hold = hold;
+    self.refresh_update_status();
+  }
+
+  fn refresh_update_status(&mut self) {
+    if let (Some(local), Some(remote)) = (&self.version_checker, &self.remote_version) {
+      self.update_status = Some(compare_versions(&local.version, &remote.version, self.update_hold.as_ref()));
+    }
+    for bundled in self.bundled_versions.iter_mut() {
+      if let Some(remote) = &bundled.remote {
+        bundled.update_status = Some(compare_versions(&bundled.local.version, &remote.version, self.update_hold.as_ref()));
+      }
+    }
+  }
+}
+
+fn compare_versions(local_version: &Version, version: &Version, hold: Option<&UpdateHold>) -> UpdateStatus {
+  let version = version.clone();
+
+  if version == *local_version {
+    UpdateStatus::UpToDate
+  } else if version < *local_version {
+    UpdateStatus::Discrepancy(version)
+  } else if hold.map_or(false, |hold| hold.holds(&version)) {
+    UpdateStatus::Held(version)
+  } else if version.major - local_version.major > 0 {
+    UpdateStatus::Major(version)
+  } else if version.minor - local_version.minor > 0 {
+    UpdateStatus::Minor(version)
+  } else {
+    UpdateStatus::Patch(version)
+  }
+}
+
+/**
+ * The Version Checker files listed in a version_files.csv: the first field of every row after the header, skipping blank
+ * rows, `#` comments and repeats.
+ */
+fn parse_version_files(csv: &str) -> Vec<String> {
+  let mut version_files: Vec<String> = vec![];
+  for file in parse_csv(csv).into_iter()
+    .filter_map(|record| record.into_iter().next())
+    .map(|field| field.trim().to_string())
+    .filter(|field| !field.is_empty() && !field.starts_with('#'))
+    .skip(1)
+  {
+    if !version_files.contains(&file) {
+      version_files.push(file);
+    }
+  }
+
+  version_files
+}
+
+fn read_version_file(mod_folder: &Path, version_filename: &str) -> Option<ModVersionMeta> {
+  if_chain! {
+    if let Ok(version_data) = std::fs::read_to_string(mod_folder.join(version_filename));
+    let mut no_comments = String::new();
+    if strip_comments(version_data.as_bytes()).read_to_string(&mut no_comments).is_ok();
+    if let Ok(normalized) = handwritten_json::normalize(&no_comments);
+    if let Ok(version) = json5::from_str::<ModVersionMeta>(&normalized);
+    then {
+      Some(version)
+    } else {
+      None
+    }
+  }
+}
+
+/**
+ * Every pair of the given mods that shouldn't be enabled together, see `ModEntry::excludes`. Each pair is listed once.
+ */
+pub fn exclusions<'a>(mods: &[&'a ModEntry]) -> Vec<(&'a ModEntry, &'a ModEntry)> {
+  mods.iter()
+    .enumerate()
+    .flat_map(|(idx, entry)| mods[idx + 1..].iter()
+      .filter(move |other| entry.excludes(other))
+      .map(move |other| (*entry, *other))
+    )
+    .collect()
+}
+
+  /**
+   * Parses a given version into a four-tuple of the assumed components.
+   * Assumptions:
+   * - The first component is always EITHER 0 and thus the major component OR it has been omitted and the first component is the minor component
+   * - If there are two components it is either the major and minor components OR minor and patch OR minor and RC (release candidate)
+   * - If there are three components it is either the major, minor and patch OR major, minor and RC OR minor, patch and RC
+   * - If there are four components then the first components MUST be 0 and MUST be the major component, and the following components
+        are the minor, patch and RC components
+   */
+pub fn parse_game_version(text: &str) -> (Option<String>, Option<String>, Option<String>, Option<String>) {
+  let components: Vec<&str> = VERSION_REGEX.split(text).filter(|c| !c.is_empty()).collect();
+
+  match components.as_slice() {
+    [major, minor] if major == &"0" => {
+      // text = format!("{}.{}a", major, minor);
+      (Some(major.to_string()), Some(minor.to_string()), None, None)
+    }
+    [minor, patch_rc] => {
+      // text = format!("0.{}a-RC{}", minor, rc);
+      if text.contains("a-RC") {
+        (Some("0".to_string()), Some(minor.to_string()), None, Some(patch_rc.to_string()))
+      } else {
+        (Some("0".to_string()), Some(minor.to_string()), Some(patch_rc.to_string()), None)
+      }
+    }
+    [major, minor, patch_rc] if major == &"0" => {
+      // text = format!("{}.{}a-RC{}", major, minor, rc);
+      if text.contains("a-RC") {
+        (Some(major.to_string()), Some(minor.to_string()), None, Some(patch_rc.to_string()))
+      } else {
+        (Some(major.to_string()), Some(minor.to_string()), Some(patch_rc.to_string()), None)
+      }
+    }
+    [minor, patch, rc] => {
+      // text = format!("0.{}.{}a-RC{}", minor, patch, rc);
+      (Some("0".to_string()), Some(minor.to_string()), Some(patch.to_string()), Some(rc.to_string()))
+    }
+    [major, minor, patch, rc] if major == &"0" => {
+      // text = format!("{}.{}.{}a-RC{}", major, minor, patch, rc);
+      (Some(major.to_string()), Some(minor.to_string()), Some(patch.to_string()), Some(rc.to_string()))
+    }
+    _ => {
+      dbg!("Failed to normalise mod's quoted game version");
+      (None, None, None, None)
+    }
+  }
+}
+
+#[derive(Debug, Clone, Deserialize, Eq, Ord)]
+pub struct ModVersionMeta {
+  #[serde(alias="masterVersionFile")]
+  pub remote_url: String,
+  #[serde(alias="directDownloadURL")]
+  #[serde(default)]
+  pub direct_download_url: Option<String>,
+  /**
+   * Hex encoded SHA-256 digest of the archive at `direct_download_url`, if the mod author publishes one.
+   */
+  #[serde(alias="directDownloadSHA256")]
+  #[serde(default)]
+  pub direct_download_sha256: Option<String>,
+  /**
+   * Size in bytes of the archive at `direct_download_url`, if the mod author publishes it.
+   */
+  #[serde(alias="directDownloadSize")]
+  #[serde(default)]
+  pub direct_download_size: Option<u64>,
+  #[serde(alias="changelogURL")]
+  #[serde(default)]
+  pub changelog_url: Option<String>,
+  #[serde(alias="modName")]
+  pub id: String,
+  #[serde(alias="modThreadId")]
+  #[serde(deserialize_with="deserialize_string_from_number")]
+  #[serde(default)]
+  pub fractal_id: String,
+  #[serde(alias="modNexusId")]
+  #[serde(deserialize_with="deserialize_string_from_number")]
+  #[serde(default)]
+  pub nexus_id: String,
+  #[serde(alias="modVersion")]
+  pub version: Version
+}
+
+impl PartialEq for ModVersionMeta {
+  fn eq(&self, other: &Self) -> bool {
+    self.id == other.id && self.version == other.version
+  }
+}
+
+impl PartialOrd for ModVersionMeta {
+  fn partial_cmp(&self, other: &Self) -> Option<std::cmp::Ordering> {
+    self.version.partial_cmp(&other.version)
+  }
+}
+
+#[derive(Debug, Clone, Deserialize, PartialEq, Eq, PartialOrd, Ord)]
+pub struct Version {
+  #[serde(deserialize_with="deserialize_number_from_string")]
+  pub major: i32,
+  #[serde(deserialize_with="deserialize_number_from_string")]
+  pub minor: i32,
+  #[serde(default)]
+  #[serde(deserialize_with="deserialize_string_from_number")]
+  pub patch: String
+}
+
+impl Display for Version {
+  fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::result::Result<(), std::fmt::Error> {
+    if self.patch.len() > 0 {
+      write!(f, "{}.{}.{}", self.major, self.minor, self.patch)
+    } else {
+      write!(f, "{}.{}", self.major, self.minor)
+    }
+  }
+}
+
+#[derive(Serialize, Deserialize)]
+pub struct EnabledMods {
+  #[serde(rename = "enabledMods")]
+  pub enabled_mods: Vec<String>
+}
+
+impl EnabledMods {
+  /**
+   * A missing enabled_mods.json is treated the same as one with no mods enabled - the game creates it lazily.
+   */
+  pub fn load(path: &PathBuf) -> Result<EnabledMods, LoadError> {
+    if !path.exists() {
+      return Ok(EnabledMods { enabled_mods: vec![] })
+    }
+
+    let enabled_mods_text = std::fs::read_to_string(path)
+      .map_err(|_| LoadError::ReadError)?;
+
+    serde_json::from_str::<EnabledMods>(&enabled_mods_text).map_err(|_| LoadError::FormatError)
+  }
+
+  /**
+   * Writes to a sibling file first and renames it over the original, so the game never sees a half-written list.
+   */
+  pub async fn save(self, path: PathBuf) -> Result<(), SaveError> {
+    util::save_json(path, self).await
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  #[test]
+  fn skips_unreadable_dependencies() {
+    let mod_info = ModEntry::from_mod_info(r#"{
+      id: "a",
+      name: "Mod a",
+      version: "1.0",
+      description: "",
+      gameVersion: "0.95a",
+      dependencies: [
+        { name: "No ID" },
+        { id: "lw_lazylib", name: "LazyLib" },
+        { id: 5 },
+      ],
+    }"#).expect("Parse mod_info.json");
+
+    assert_eq!(mod_info.dependencies.len(), 1);
+    assert_eq!(mod_info.dependencies[0].id, "lw_lazylib");
+  }
+
+  #[test]
+  fn version_files_quoted_path_with_comma() {
+    let csv = "version file\n\"data/config/version/a, b.version\",extra\n";
+
+    assert_eq!(parse_version_files(csv), vec!["data/config/version/a, b.version"]);
+  }
+
+  #[test]
+  fn version_files_crlf_with_bom() {
+    let csv = "\u{feff}version file\r\ndata/config/version/mod.version\r\n";
+
+    assert_eq!(parse_version_files(csv), vec!["data/config/version/mod.version"]);
+  }
+
+  #[test]
+  fn version_files_skip_comments() {
+    let csv = "# Version Checker files\nversion file\n#data/config/version/old.version\ndata/config/version/mod.version\n";
+
+    assert_eq!(parse_version_files(csv), vec!["data/config/version/mod.version"]);
+  }
+
+  #[test]
+  fn version_files_several_rows() {
+    let csv = "version file\n\ndata/config/version/a.version\n,\ndata/config/version/b.version\ndata/config/version/a.version";
+
+    assert_eq!(parse_version_files(csv), vec!["data/config/version/a.version", "data/config/version/b.version"]);
+  }
+}
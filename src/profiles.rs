@@ -0,0 +1,159 @@
+use std::{path::PathBuf, collections::BTreeMap, fmt::Display};
+use serde::{Serialize, Deserialize};
+
+use crate::{LoadError, SaveError};
+use crate::config::config_dir;
+use crate::util;
+
+/**
+ * Named sets of enabled mods, stored alongside the manager's config rather than in the game folder.
+ */
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct Profiles {
+  pub active: Option<String>,
+  pub profiles: BTreeMap<String, Vec<String>>,
+}
+
+#[derive(Debug, Clone)]
+pub enum ProfileError {
+  NotFound(String),
+  AlreadyExists(String),
+  InvalidName,
+}
+
+impl Display for ProfileError {
+  fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::result::Result<(), std::fmt::Error> {
+    match self {
+      ProfileError::NotFound(name) => write!(f, "No profile named \"{}\" exists.", name),
+      ProfileError::AlreadyExists(name) => write!(f, "A profile named \"{}\" already exists.", name),
+      ProfileError::InvalidName => write!(f, "Profile names cannot be empty."),
+    }
+  }
+}
+
+impl Profiles {
+  pub async fn path(try_make: bool) -> PathBuf {
+    config_dir(try_make).await.join("profiles.json")
+  }
+
+  /**
+   * Having never saved a profile is not an error, so a missing file loads as an empty set of profiles.
+   */
+  pub async fn load() -> Result<Profiles, LoadError> {
+    use tokio::fs;
+    use tokio::io::AsyncReadExt;
+
+    let path = Profiles::path(false).await;
+    if !path.exists() {
+      return Ok(Profiles::default())
+    }
+
+    let mut profiles_file = fs::File::open(path)
+      .await
+      .map_err(|_| LoadError::NoSuchFile)?;
+
+    let mut profiles_string = String::new();
+    profiles_file.read_to_string(&mut profiles_string)
+      .await
+      .map_err(|_| LoadError::ReadError)?;
+
+    serde_json::from_str::<Profiles>(&profiles_string).map_err(|_| LoadError::FormatError)
+  }
+
+  pub async fn save(self) -> Result<(), SaveError> {
+    util::save_json(Profiles::path(true).await, self).await
+  }
+
+  pub fn names(&self) -> Vec<String> {
+    self.profiles.keys().cloned().collect()
+  }
+
+  pub fn get(&self, name: &str) -> Option<&Vec<String>> {
+    self.profiles.get(name)
+  }
+
+  /**
+   * Whether the given set of enabled mods differs from the active profile. Always false if no profile is active.
+   */
+  pub fn is_modified(&self, enabled_mods: &[String]) -> bool {
+    match self.active.as_ref().and_then(|name| self.profiles.get(name)) {
+      Some(profile) => {
+        profile.len() != enabled_mods.len() || !enabled_mods.iter().all(|id| profile.contains(id))
+      },
+      None => false
+    }
+  }
+
+  /**
+   * Saves the given mods as a new profile and makes it the active one.
+   */
+  pub fn create(&mut self, name: String, mut enabled_mods: Vec<String>) -> Result<(), ProfileError> {
+    let name = validate_name(name)?;
+    if self.profiles.contains_key(&name) {
+      return Err(ProfileError::AlreadyExists(name))
+    }
+
+    enabled_mods.sort();
+    self.profiles.insert(name.clone(), enabled_mods);
+    self.active = Some(name);
+
+    Ok(())
+  }
+
+  /**
+   * Overwrites an existing profile with the given mods.
+   */
+  pub fn overwrite(&mut self, name: &str, mut enabled_mods: Vec<String>) -> Result<(), ProfileError> {
+    let profile = self.profiles.get_mut(name).ok_or_else(|| ProfileError::NotFound(name.to_string()))?;
+
+    enabled_mods.sort();
+    *profile = enabled_mods;
+
+    Ok(())
+  }
+
+  pub fn rename(&mut self, name: &str, new_name: String) -> Result<(), ProfileError> {
+    let new_name = validate_name(new_name)?;
+    if self.profiles.contains_key(&new_name) {
+      return Err(ProfileError::AlreadyExists(new_name))
+    }
+
+    let profile = self.profiles.remove(name).ok_or_else(|| ProfileError::NotFound(name.to_string()))?;
+    self.profiles.insert(new_name.clone(), profile);
+    if self.active.as_deref() == Some(name) {
+      self.active = Some(new_name);
+    }
+
+    Ok(())
+  }
+
+  pub fn duplicate(&mut self, name: &str, new_name: String) -> Result<(), ProfileError> {
+    let new_name = validate_name(new_name)?;
+    if self.profiles.contains_key(&new_name) {
+      return Err(ProfileError::AlreadyExists(new_name))
+    }
+
+    let profile = self.profiles.get(name).cloned().ok_or_else(|| ProfileError::NotFound(name.to_string()))?;
+    self.profiles.insert(new_name, profile);
+
+    Ok(())
+  }
+
+  pub fn delete(&mut self, name: &str) -> Result<(), ProfileError> {
+    self.profiles.remove(name).ok_or_else(|| ProfileError::NotFound(name.to_string()))?;
+    if self.active.as_deref() == Some(name) {
+      self.active = None;
+    }
+
+    Ok(())
+  }
+}
+
+fn validate_name(name: String) -> Result<String, ProfileError> {
+  let name = name.trim();
+  if name.is_empty() {
+    Err(ProfileError::InvalidName)
+  } else {
+    Ok(name.to_string())
+  }
+}
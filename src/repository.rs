@@ -0,0 +1,345 @@
+use std::{path::PathBuf, collections::HashMap};
+
+use crate::{LoadError, SaveError};
+use crate::mods::{self, ModEntry, ModVersionMeta, EnabledMods, Dependency, DependencyStatus, UpdateHold, UpdateStatus};
+use crate::installer::{self, InstallError, ProgressReporter, DownloadOptions, ArchiveChecksum, ArchivePreview};
+use crate::vmparams::VMParams;
+use crate::conflicts::{ModFiles, ConflictReport};
+use crate::util;
+use crate::version_cache::{self, CachedVersion};
+
+/**
+ * A Starsector installation, rooted at the folder containing the `mods` directory and `vmparams` file.
+ */
+#[derive(Debug, Clone)]
+pub struct GameInstall {
+  pub root: PathBuf
+}
+
+impl GameInstall {
+  pub fn new(root: PathBuf) -> Self {
+    GameInstall {
+      root
+    }
+  }
+
+  pub fn mods_dir(&self) -> PathBuf {
+    self.root.join("mods")
+  }
+
+  pub fn scan_mods(&self) -> Result<ModRepository, LoadError> {
+    ModRepository::scan(self.mods_dir())
+  }
+
+  pub async fn starsector_version(&self) -> Result<String, LoadError> {
+    util::get_starsector_version(self.root.clone()).await
+  }
+
+  pub async fn vmparams(&self) -> Result<VMParams, LoadError> {
+    VMParams::load(self.root.clone()).await
+  }
+
+  pub async fn save_vmparams(&self, vmparams: VMParams) -> Result<(), SaveError> {
+    vmparams.save(self.root.clone()).await
+  }
+}
+
+/**
+ * The mods found in a mods directory, along with whether they are enabled.
+ */
+#[derive(Debug, Clone)]
+pub struct ModRepository {
+  mods_dir: PathBuf,
+  pub mods: HashMap<String, ModEntry>,
+  pub download_options: DownloadOptions,
+}
+
+#[derive(Debug, Clone)]
+pub enum InstallOutcome {
+  Installed(String),
+  Replaced(String),
+  Duplicate(String, PathBuf),
+}
+
+impl ModRepository {
+  pub fn scan(mods_dir: PathBuf) -> Result<Self, LoadError> {
+    let EnabledMods { enabled_mods } = EnabledMods::load(&mods_dir.join("enabled_mods.json"))?;
+
+    let dir_iter = std::fs::read_dir(&mods_dir).map_err(|_| LoadError::NoSuchFile)?;
+    let mods = dir_iter
+      .filter_map(|entry| entry.ok())
+      .filter(|entry| {
+        if let Ok(file_type) = entry.file_type() {
+          file_type.is_dir()
+        } else {
+          false
+        }
+      })
+      .filter_map(|entry| {
+        if let Ok(mut mod_info) = ModEntry::from_file(&entry.path()) {
+          mod_info.enabled = enabled_mods.iter().any(|id| mod_info.id.eq(id));
+          Some((mod_info.id.clone(), mod_info))
+        } else {
+          dbg!(entry.path());
+          None
+        }
+      })
+      .collect();
+
+    Ok(ModRepository {
+      mods_dir,
+      mods,
+      download_options: DownloadOptions::default(),
+    })
+  }
+
+  pub fn mods_dir(&self) -> &PathBuf {
+    &self.mods_dir
+  }
+
+  pub fn get(&self, id: &str) -> Option<&ModEntry> {
+    self.mods.get(id)
+  }
+
+  /**
+   * Returns false if no mod with the given ID is installed.
+   */
+  pub fn set_enabled(&mut self, id: &str, enabled: bool) -> bool {
+    if let Some(entry) = self.mods.get_mut(id) {
+      entry.enabled = enabled;
+
+      true
+    } else {
+      false
+    }
+  }
+
+  pub fn set_all_enabled(&mut self, enabled: bool) {
+    self.mods.values_mut().for_each(|entry| entry.enabled = enabled);
+  }
+
+  /**
+   * Enables exactly the given mods and disables every other one.
+   * Returns the IDs that aren't installed, which are left out of the enabled list.
+   */
+  pub fn apply_profile(&mut self, enabled_mods: &[String]) -> Vec<String> {
+    self.mods.iter_mut().for_each(|(id, entry)| entry.enabled = enabled_mods.contains(id));
+
+    enabled_mods.iter()
+      .filter(|id| !self.mods.contains_key(*id))
+      .cloned()
+      .collect()
+  }
+
+  pub fn dependency_statuses(&self, id: &str) -> Vec<(Dependency, DependencyStatus)> {
+    self.mods.get(id)
+      .map(|entry| entry.dependency_statuses(|id| self.mods.get(id)))
+      .unwrap_or_default()
+  }
+
+  /**
+   * The IDs of enabled mods that declare a dependency on the given mod.
+   */
+  pub fn dependents(&self, id: &str) -> Vec<String> {
+    self.mods.values()
+      .filter(|entry| entry.enabled && entry.depends_on(id))
+      .map(|entry| entry.id.clone())
+      .collect()
+  }
+
+  /**
+   * Every pair of enabled mods that can't be used together, by ID, see `ModEntry::excludes`.
+   */
+  pub fn enabled_exclusions(&self) -> Vec<(String, String)> {
+    let enabled: Vec<&ModEntry> = self.mods.values().filter(|entry| entry.enabled).collect();
+
+    mods::exclusions(&enabled).into_iter()
+      .map(|(entry, other)| (entry.id.clone(), other.id.clone()))
+      .collect()
+  }
+
+  /**
+   * Walks the folder of every enabled mod looking for files shipped by more than one of them. This touches the disk, so it can be slow.
+   */
+  pub fn file_conflicts(&self) -> ConflictReport {
+    let files = ModFiles::scan_all(self.mods.values().filter(|entry| entry.enabled).cloned().collect());
+
+    ConflictReport::new(&files)
+  }
+
+  pub fn enabled_mods(&self) -> EnabledMods {
+    EnabledMods {
+      enabled_mods: self.mods.iter()
+        .filter_map(|(id, entry)| entry.enabled.then(|| id.clone()))
+        .collect()
+    }
+  }
+
+  pub async fn save_enabled(&self) -> Result<(), SaveError> {
+    self.enabled_mods().save(self.mods_dir.join("enabled_mods.json")).await
+  }
+
+  pub fn version_checks(&self) -> Vec<ModVersionMeta> {
+    self.mods.values()
+      .flat_map(|entry| entry.version_checks())
+      .collect()
+  }
+
+  /**
+   * Sets the hold on each mod's updates from the given holds, by mod ID, clearing holds on mods that aren't listed.
+   */
+  pub fn apply_update_holds(&mut self, holds: &HashMap<String, UpdateHold>) {
+    for (id, entry) in self.mods.iter_mut() {
+      entry.set_update_hold(holds.get(id).cloned());
+    }
+  }
+
+  /**
+   * Records the result of a remote version check against the mod it was for, given the ID of the check.
+   */
+  pub fn set_master_version(&mut self, check_id: &str, res: Result<ModVersionMeta, String>) {
+    if let Some(entry) = self.mods.values_mut().find(|entry| entry.has_version_check(check_id)) {
+      entry.set_master_version(check_id, res);
+    }
+  }
+
+  /**
+   * Shows the update status each mod had when it was last checked, as found in `cache`, until it's checked again.
+   */
+  pub fn load_cached_versions(&mut self, cache: &HashMap<String, CachedVersion>) {
+    for (check_id, remote, fetched_at) in util::cached_master_versions(&self.version_checks(), cache) {
+      if let Some(entry) = self.mods.values_mut().find(|entry| entry.has_version_check(&check_id)) {
+        entry.set_cached_master_version(&check_id, remote, fetched_at);
+      }
+    }
+  }
+
+  /**
+   * Fetches the remote Version Checker file of every mod that has one and updates each mod's update status.
+   * Mods whose check fails keep the status they had when last checked, if any, marked as cached.
+   */
+  pub async fn check_updates(&mut self) {
+    let cache = version_cache::load().await;
+    self.load_cached_versions(&cache);
+
+    let handles: Vec<_> = self.version_checks()
+      .into_iter()
+      .map(|version| {
+        let cached = cache.get(&version.id).cloned();
+
+        tokio::spawn(util::get_master_version(version, cached))
+      })
+      .collect();
+
+    let mut updates = HashMap::new();
+    for handle in handles {
+      if let Ok((id, res, update)) = handle.await {
+        if let Some(update) = update {
+          updates.insert(id.clone(), update);
+        }
+        self.set_master_version(&id, res);
+      }
+    }
+
+    version_cache::save(updates).await;
+  }
+
+  /**
+   * Installs every mod found in the given archive or folder, each with its own outcome.
+   * An existing mod with the same ID, or an existing folder with the same name, is only replaced if `replace` is set.
+   */
+  pub async fn install(&self, source: PathBuf, replace: bool) -> Result<Vec<Result<InstallOutcome, InstallError>>, InstallError> {
+    let mut outcomes = vec![];
+    for (mod_folder, mod_info) in installer::stage_all(source, ProgressReporter::default()).await? {
+      let id = mod_info.id.clone();
+      outcomes.push(self.install_staged(mod_folder, mod_info, replace).await
+        .map_err(|err| InstallError::Any { detail: format!("{}: {}", id, err) }));
+    }
+
+    Ok(outcomes)
+  }
+
+  /**
+   * Lists what installing the given archive or folder would install, and what each mod in it would replace, without
+   * changing anything.
+   */
+  pub async fn preview(&self, source: PathBuf) -> Result<ArchivePreview, InstallError> {
+    let mut preview = installer::preview(source).await?;
+    preview.mark_replacements(|id| self.existing_path(id));
+
+    Ok(preview)
+  }
+
+  /**
+   * Where a mod with the given ID would be installed to, if something is already there.
+   */
+  fn existing_path(&self, id: &str) -> Option<PathBuf> {
+    if let Some(entry) = self.mods.get(id) {
+      Some(entry.path.clone())
+    } else if self.mods_dir.join(id).exists() {
+      Some(self.mods_dir.join(id))
+    } else {
+      None
+    }
+  }
+
+  async fn install_staged(&self, mod_folder: installer::HybridPath, mod_info: ModEntry, replace: bool) -> Result<InstallOutcome, InstallError> {
+    match self.existing_path(&mod_info.id) {
+      Some(old_path) if replace => {
+        installer::replace(mod_folder, old_path, ProgressReporter::default()).await?;
+
+        Ok(InstallOutcome::Replaced(mod_info.id))
+      },
+      Some(old_path) => Ok(InstallOutcome::Duplicate(mod_info.id, old_path)),
+      None => {
+        installer::install_to(mod_folder, self.mods_dir.join(&mod_info.id), ProgressReporter::default()).await?;
+
+        Ok(InstallOutcome::Installed(mod_info.id))
+      }
+    }
+  }
+
+  /**
+   * Moves the given mod into the trash, or into `move_to`, and removes it from the enabled mods list.
+   */
+  pub async fn uninstall(&mut self, id: &str, move_to: Option<PathBuf>) -> Result<(), InstallError> {
+    let entry = self.mods.get(id)
+      .ok_or_else(|| InstallError::Any { detail: format!("No mod with ID `{}` is installed", id) })?;
+
+    installer::uninstall(entry.path.clone(), move_to).await?;
+    self.mods.remove(id);
+
+    self.save_enabled().await
+      .map_err(|err| InstallError::Any { detail: format!("Removed `{}` but failed to update enabled_mods.json: {:?}", id, err) })
+  }
+
+  /**
+   * As with `install`, but the archive is first downloaded from the given URL.
+   */
+  pub async fn install_from_url(&self, url: String, replace: bool) -> Result<Vec<Result<InstallOutcome, InstallError>>, InstallError> {
+    let file = installer::download(url, &ArchiveChecksum::default(), self.download_options, ProgressReporter::default()).await?;
+
+    self.install(file.path().to_path_buf(), replace).await
+  }
+
+  /**
+   * Downloads and installs the newest version of the given mod, if its remote Version Checker file provides a direct download.
+   * `check_updates` must have been run beforehand. Mods whose updates are held, or that are up to date, are left alone.
+   */
+  pub async fn update(&self, id: &str) -> Result<String, InstallError> {
+    let entry = self.mods.get(id)
+      .ok_or_else(|| InstallError::Any { detail: format!("No mod with ID `{}` is installed", id) })?;
+    let remote = entry.remote_version.as_ref()
+      .ok_or_else(|| InstallError::Any { detail: format!("No remote version information for `{}`", id) })?;
+    if matches!(entry.update_status, Some(UpdateStatus::Held(_))) || matches!(&entry.update_hold, Some(hold) if hold.holds(&remote.version)) {
+      return Err(InstallError::UpdateHeld { id: id.to_string() })
+    }
+    if !matches!(&entry.update_status, Some(status) if status.is_update_available()) {
+      return Err(InstallError::NoUpdate { id: id.to_string() })
+    }
+    let url = remote.direct_download_url.clone()
+      .ok_or_else(|| InstallError::ManualUpdate { id: id.to_string() })?;
+
+    installer::auto_update(url, remote.version.to_string(), entry.path.clone(), ArchiveChecksum::from(remote), self.download_options, ProgressReporter::default()).await
+  }
+}
@@ -171,15 +171,17 @@ pub mod highlight_background {
 }
 
 use iced::container;
-use crate::gui::mod_list::{UpdateStatus, UpdateStatusTTPatch};
+use crate::gui::mod_list::{UpdateStatus, UpdateStatusTTPatch, UpdateStatusContainerPatch};
 
-impl From<&UpdateStatus> for Box<dyn container::StyleSheet> {
-  fn from(theme: &UpdateStatus) -> Self {
+impl From<UpdateStatusContainerPatch> for Box<dyn container::StyleSheet> {
+  fn from(wrapper: UpdateStatusContainerPatch) -> Self {
+    let UpdateStatusContainerPatch(theme) = wrapper;
     match theme {
       UpdateStatus::Major(_) | UpdateStatus::Minor(_) | UpdateStatus::Patch(_) => update::major::Container.into(),
       UpdateStatus::UpToDate => update::up_to_date::Container.into(),
       UpdateStatus::Error => update::error::Container.into(),
       UpdateStatus::Discrepancy(_) => update::discrepancy::Container.into(),
+      UpdateStatus::Held(_) => update::held::Container.into(),
     }
   }
 }
@@ -192,6 +194,7 @@ impl From<UpdateStatusTTPatch> for Box<dyn container::StyleSheet> {
       UpdateStatus::UpToDate => update::up_to_date::Tooltip.into(),
       UpdateStatus::Error => update::error::Tooltip.into(),
       UpdateStatus::Discrepancy(_) => update::discrepancy::Tooltip.into(),
+      UpdateStatus::Held(_) => update::held::Tooltip.into(),
     }
   }
 }
@@ -316,6 +319,36 @@ pub mod update {
       }
     }
   }
+
+  pub mod held {
+    pub struct Container;
+    pub struct Tooltip;
+
+    fn style() -> super::container::Style {
+      super::container::Style {
+        background: super::Color::from_rgb8(0x75, 0x75, 0x75).into(),
+        text_color: Some(super::Color::WHITE),
+        ..super::container::Style::default()
+      }
+    }
+
+    impl super::container::StyleSheet for Container {
+      fn style(&self) -> super::container::Style {
+        style()
+      }
+    }
+
+    impl super::container::StyleSheet for Tooltip {
+      fn style(&self) -> super::container::Style {
+        super::container::Style {
+          border_color: super::Color::BLACK,
+          border_width: 1.0,
+          border_radius: 5.0,
+          ..style()
+        }
+      }
+    }
+  }
 }
 
 pub mod hyperlink_block {
@@ -0,0 +1,198 @@
+use std::{path::PathBuf, fmt::Display};
+use serde::{Serialize, Deserialize};
+use tokio::task;
+use remove_dir_all::remove_dir_all;
+
+use crate::config::config_dir;
+use crate::installer::{self, InstallError};
+use crate::mods::ModEntry;
+use crate::util::now;
+
+const INFO_FILE: &str = "trash_info.json";
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum RemovalReason {
+  Uninstalled,
+  Replaced,
+}
+
+impl Display for RemovalReason {
+  fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::result::Result<(), std::fmt::Error> {
+    match self {
+      RemovalReason::Uninstalled => write!(f, "Uninstalled"),
+      RemovalReason::Replaced => write!(f, "Replaced"),
+    }
+  }
+}
+
+/**
+ * A mod folder that was uninstalled or replaced by the manager, kept in the trash until it is restored or purged.
+ * Each entry lives in its own folder inside the trash, next to a `trash_info.json` describing where it came from.
+ */
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct TrashEntry {
+  pub id: String,
+  pub name: String,
+  pub version: String,
+  pub folder_name: String,
+  pub original_path: PathBuf,
+  /**
+   * Seconds since the Unix epoch.
+   */
+  pub removed_at: u64,
+  pub reason: RemovalReason,
+  #[serde(skip)]
+  pub path: PathBuf,
+}
+
+pub async fn trash_dir(try_make: bool) -> PathBuf {
+  config_dir(try_make).await.join("trash")
+}
+
+/**
+ * Moves the mod folder at `path` into the trash. If the folder doesn't contain a readable mod_info.json the folder name
+ * stands in for the mod's ID and name.
+ */
+pub async fn move_to_trash(path: PathBuf, reason: RemovalReason) -> Result<TrashEntry, InstallError> {
+  move_to_trash_as(path.clone(), path, reason).await
+}
+
+/**
+ * As with `move_to_trash`, for a mod that has already been moved aside from `original_path`, which is where it will be restored to.
+ */
+pub async fn move_to_trash_as(path: PathBuf, original_path: PathBuf, reason: RemovalReason) -> Result<TrashEntry, InstallError> {
+  let folder_name = original_path.file_name()
+    .ok_or_else(|| InstallError::Any { detail: String::from("Mod folder has no name") })?
+    .to_string_lossy()
+    .to_string();
+  let (id, name, version) = match ModEntry::from_file(&path) {
+    Ok(entry) => (entry.id, entry.name, entry.version.to_string()),
+    Err(_) => (folder_name.clone(), folder_name.clone(), String::from("Unknown"))
+  };
+  let removed_at = now();
+
+  let trash = trash_dir(true).await;
+  let mut entry_dir = trash.join(format!("{}-{}", removed_at, folder_name));
+  let mut suffix = 1;
+  while entry_dir.exists() {
+    entry_dir = trash.join(format!("{}-{}-{}", removed_at, folder_name, suffix));
+    suffix += 1;
+  }
+  tokio::fs::create_dir_all(&entry_dir).await.map_err(|source| InstallError::Io { source })?;
+
+  let entry = TrashEntry {
+    id,
+    name,
+    version,
+    folder_name: folder_name.clone(),
+    original_path,
+    removed_at,
+    reason,
+    path: entry_dir.clone(),
+  };
+  let json = serde_json::to_string_pretty(&entry)
+    .map_err(|err| InstallError::Any { detail: format!("Failed to describe trashed mod: {}", err) })?;
+  tokio::fs::write(entry_dir.join(INFO_FILE), json).await.map_err(|source| InstallError::Io { source })?;
+
+  match installer::relocate(path, entry_dir.join(&folder_name)).await {
+    Ok(()) => Ok(entry),
+    // The trash holds a complete copy, so it's kept, but the caller still has to deal with what's left behind
+    Err(err @ InstallError::LeftBehind { .. }) => Err(err),
+    Err(err) => {
+      let _ = task::spawn_blocking(move || remove_dir_all(entry_dir)).await;
+
+      Err(err)
+    }
+  }
+}
+
+impl TrashEntry {
+  /**
+   * Every entry in the trash, most recently removed first. Entries whose info file is missing or unreadable are skipped.
+   */
+  pub async fn list() -> Vec<TrashEntry> {
+    let trash = trash_dir(false).await;
+
+    task::spawn_blocking(move || {
+      let mut entries: Vec<TrashEntry> = std::fs::read_dir(trash)
+        .map(|dir_iter| dir_iter
+          .filter_map(|entry| entry.ok())
+          .filter_map(|entry| {
+            let info = std::fs::read_to_string(entry.path().join(INFO_FILE)).ok()?;
+            let mut trash_entry = serde_json::from_str::<TrashEntry>(&info).ok()?;
+            trash_entry.path = entry.path();
+
+            Some(trash_entry)
+          })
+          .collect()
+        )
+        .unwrap_or_default();
+      entries.sort_by(|left, right| right.removed_at.cmp(&left.removed_at));
+
+      entries
+    }).await.unwrap_or_default()
+  }
+
+  /**
+   * The trashed mod folder itself.
+   */
+  pub fn mod_path(&self) -> PathBuf {
+    self.path.join(&self.folder_name)
+  }
+
+  /**
+   * Whether something now occupies the folder this mod was removed from, such as the version that replaced it.
+   */
+  pub fn is_blocked(&self) -> bool {
+    self.original_path.exists()
+  }
+
+  /**
+   * Moves the mod back to where it was removed from. Whatever occupies that folder now is moved into the trash in its
+   * place if `replace_existing` is set, otherwise restoring fails.
+   */
+  pub async fn restore(self, replace_existing: bool) -> Result<PathBuf, InstallError> {
+    if self.is_blocked() {
+      if replace_existing {
+        move_to_trash(self.original_path.clone(), RemovalReason::Replaced).await?;
+      } else {
+        return Err(InstallError::Any { detail: format!("{} already exists", self.original_path.display()) })
+      }
+    }
+
+    installer::relocate(self.mod_path(), self.original_path.clone()).await?;
+    let original_path = self.original_path.clone();
+    self.delete().await?;
+
+    Ok(original_path)
+  }
+
+  /**
+   * Permanently deletes the entry.
+   */
+  pub async fn delete(self) -> Result<(), InstallError> {
+    task::spawn_blocking(move || remove_dir_all(self.path)).await
+      .expect("Run blocking dir removal")
+      .map_err(|source| InstallError::Io { source })
+  }
+}
+
+/**
+ * Permanently deletes every entry removed more than `retention_days` days ago. A retention of 0 keeps entries forever.
+ * Returns the number of entries deleted.
+ */
+pub async fn purge(retention_days: u32) -> usize {
+  if retention_days == 0 {
+    return 0
+  }
+
+  let cutoff = now().saturating_sub(retention_days as u64 * 86400);
+  let mut purged = 0;
+  for entry in TrashEntry::list().await.into_iter().filter(|entry| entry.removed_at < cutoff) {
+    if entry.delete().await.is_ok() {
+      purged += 1;
+    }
+  }
+
+  purged
+}
@@ -0,0 +1,353 @@
+use std::{io::{self, Read}, path::{Path, PathBuf}, collections::HashMap, sync::{Arc, Mutex}, time::{SystemTime, UNIX_EPOCH}};
+use if_chain::if_chain;
+use json_comments::strip_comments;
+use lazy_static::lazy_static;
+use serde::{Serialize, de::DeserializeOwned};
+use tokio::{task, sync::Semaphore};
+
+use crate::{LoadError, SaveError};
+use crate::mods::ModVersionMeta;
+use crate::version_cache::CachedVersion;
+
+/**
+ * How many Version Checker files are fetched from the same host at once, so a refresh doesn't hammer the forum or GitHub.
+ */
+const MAX_REQUESTS_PER_HOST: usize = 4;
+
+/**
+ * Changelogs are cut short after this many bytes, as they're only shown to help judge a single update.
+ */
+const MAX_CHANGELOG_BYTES: usize = 64 * 1024;
+
+lazy_static! {
+  static ref CLIENT: reqwest::Client = reqwest::Client::new();
+  static ref HOST_LIMITS: Mutex<HashMap<String, Arc<Semaphore>>> = Mutex::new(HashMap::new());
+}
+
+/**
+ * Fetches the remote Version Checker file for `local`, only downloading it if it has changed since `cached` was fetched.
+ * Also returns the file to cache in place of `cached`, if the check succeeded, for the caller to save along with the rest of
+ * the refresh with `version_cache::save`.
+ */
+pub async fn get_master_version(local: ModVersionMeta, cached: Option<CachedVersion>) -> (String, Result<ModVersionMeta, String>, Option<CachedVersion>) {
+  let cached = cached.filter(|cached| cached.url == local.remote_url);
+  let res = send_request(local.remote_url.clone(), cached.as_ref(), None).await;
+
+  match res {
+    Err(err) => (local.id, Err(err), None),
+    Ok(None) => {
+      // Only a cached file is sent with conditions, so there's always one to fall back on
+      let cached = cached.expect("Cached version file for unmodified response");
+      let res = parse_version_file(&cached.body);
+      let update = res.is_ok().then(|| cached.refreshed());
+
+      (local.id, res, update)
+    },
+    Ok(Some(Fetched { body, etag, last_modified, .. })) => {
+      let res = parse_version_file(&body);
+      let update = res.is_ok().then(|| CachedVersion::new(local.remote_url.clone(), body, etag, last_modified));
+
+      (local.id, res, update)
+    }
+  }
+}
+
+/**
+ * The remote Version Checker files for the given mods found in `cache`, with when each was fetched, for showing update
+ * statuses before they've been checked again.
+ */
+pub fn cached_master_versions(locals: &[ModVersionMeta], cache: &HashMap<String, CachedVersion>) -> Vec<(String, ModVersionMeta, u64)> {
+  locals.iter()
+    .filter_map(|local| {
+      let entry = cache.get(&local.id).filter(|entry| entry.url == local.remote_url)?;
+
+      parse_version_file(&entry.body).ok().map(|remote| (local.id.clone(), remote, entry.fetched_at))
+    })
+    .collect()
+}
+
+/**
+ * Fetches the changelog at `url` as text, cut short if it's unreasonably long. The rest of it is never downloaded.
+ */
+pub async fn get_changelog(id: String, url: String) -> (String, Result<String, String>) {
+  let res = send_request(url, None, Some(MAX_CHANGELOG_BYTES)).await
+    .map(|fetched| match fetched {
+      Some(Fetched { body, truncated: true, .. }) => format!("{}\n\n[Changelog cut short]", body),
+      Some(fetched) => fetched.body,
+      None => String::new()
+    });
+
+  (id, res)
+}
+
+pub fn parse_version_file(body: &str) -> Result<ModVersionMeta, String> {
+  if_chain! {
+    let mut stripped = String::new();
+    if strip_comments(body.as_bytes()).read_to_string(&mut stripped).is_ok();
+    if let Ok(normalized) = handwritten_json::normalize(&stripped);
+    if let Ok(remote) = json5::from_str::<ModVersionMeta>(&normalized);
+    then {
+      Ok(remote)
+    } else {
+      Err(format!("Parse error. Payload:\n{}", body))
+    }
+  }
+}
+
+struct Fetched {
+  body: String,
+  /**
+   * Whether the body was cut short at the size it was fetched with.
+   */
+  truncated: bool,
+  etag: Option<String>,
+  last_modified: Option<String>,
+}
+
+fn host_limit(url: &str) -> Arc<Semaphore> {
+  let host = reqwest::Url::parse(url).ok()
+    .and_then(|url| url.host_str().map(str::to_string))
+    .unwrap_or_default();
+
+  HOST_LIMITS.lock().expect("Lock host request limits")
+    .entry(host)
+    .or_insert_with(|| Arc::new(Semaphore::new(MAX_REQUESTS_PER_HOST)))
+    .clone()
+}
+
+/**
+ * Fetches `url`, asking the host to skip the body if it hasn't changed since `cached` was fetched. Returns `None` if it hasn't.
+ * With `max_bytes`, the body stops being read once it reaches that size, and is marked as truncated.
+ */
+async fn send_request(url: String, cached: Option<&CachedVersion>, max_bytes: Option<usize>) -> Result<Option<Fetched>, String> {
+  use reqwest::{header, StatusCode};
+
+  let limit = host_limit(&url);
+  let _permit = limit.acquire().await.map_err(|e| format!("{:?}", e))?;
+
+  let mut request = CLIENT.get(&url);
+  if let Some(cached) = cached {
+    if let Some(etag) = &cached.etag {
+      request = request.header(header::IF_NONE_MATCH, etag);
+    }
+    if let Some(last_modified) = &cached.last_modified {
+      request = request.header(header::IF_MODIFIED_SINCE, last_modified);
+    }
+  }
+
+  let mut response = request.send()
+    .await
+    .map_err(|e| format!("{:?}", e))?
+    .error_for_status()
+    .map_err(|e| format!("{:?}", e))?;
+
+  if response.status() == StatusCode::NOT_MODIFIED && cached.is_some() {
+    return Ok(None)
+  }
+
+  let header = |name| response.headers().get(name)
+    .and_then(|value: &header::HeaderValue| value.to_str().ok())
+    .map(str::to_string);
+  let (etag, last_modified) = (header(header::ETAG), header(header::LAST_MODIFIED));
+
+  let max_bytes = max_bytes.unwrap_or(usize::MAX);
+  let mut body = Vec::new();
+  let mut truncated = false;
+  while let Some(chunk) = response.chunk().await.map_err(|e| format!("{:?}", e))? {
+    if body.len() + chunk.len() > max_bytes {
+      body.extend_from_slice(&chunk[..max_bytes - body.len()]);
+      truncated = true;
+      break;
+    }
+    body.extend_from_slice(&chunk);
+  }
+
+  // A cut can land in the middle of a character, which is dropped rather than shown as garbage
+  if let Err(err) = std::str::from_utf8(&body) {
+    if truncated && err.error_len().is_none() {
+      body.truncate(err.valid_up_to());
+    }
+  }
+
+  Ok(Some(Fetched { body: String::from_utf8_lossy(&body).into_owned(), truncated, etag, last_modified }))
+}
+
+pub async fn get_starsector_version(install_dir: PathBuf) -> Result<String, LoadError> {
+  use classfile_parser::class_parser;
+  use tokio::{task, fs};
+  use regex::bytes::Regex;
+
+  let install_dir_clone = install_dir.clone();
+  let res = task::spawn_blocking(move || {
+    let jar = std::fs::File::open(install_dir_clone.join("starsector-core").join("starfarer_obf.jar")).map_err(|_| LoadError::NoSuchFile)?;
+    let mut zip = zip::ZipArchive::new(jar).map_err(|_| LoadError::FormatError)?;
+
+    // println!("{:?}", zip.file_names().collect::<Vec<&str>>());
+
+    let mut version_class = zip.by_name("com/fs/starfarer/Version.class").map_err(|_| LoadError::NoSuchFile)?;
+
+    let mut buf: Vec<u8> = Vec::new();
+    version_class.read_to_end(&mut buf)
+      .map_err(|_| LoadError::ReadError)
+      .and_then(|_| {
+        class_parser(&buf).map_err(|_| LoadError::FormatError).map(|(_, class_file)| class_file)
+      })
+      .and_then(|class_file| {
+        class_file.fields.iter().find_map(|f| {
+          if_chain! {
+            if let Some(classfile_parser::constant_info::ConstantInfo::Utf8(name)) = class_file.const_pool.get((f.name_index as usize).wrapping_sub(1));
+            if name.utf8_string == "versionOnly";
+            if let Some(attribute) = f.attributes.first();
+            if let Ok((_, attr)) = classfile_parser::attribute_info::constant_value_attribute_parser(&attribute.info);
+            if let Some(classfile_parser::constant_info::ConstantInfo::Utf8(utf_const)) = class_file.const_pool.get(attr.constant_value_index as usize);
+            then {
+              return Some(utf_const.utf8_string.clone())
+            } else {
+              None
+            }
+          }
+        }).ok_or_else(|| LoadError::FormatError)
+      })
+  }).await
+  .map_err(|_| LoadError::ReadError)
+  .flatten();
+
+  if res.is_err() {
+    lazy_static! {
+      static ref RE: Regex = Regex::new(r"Starting Starsector (.*) launcher").unwrap();
+    }
+    fs::read(install_dir.join("starsector-core").join("starsector.log")).await
+      .map_err(|_| LoadError::ReadError)
+      .and_then(|file| {
+        RE.captures(&file)
+          .and_then(|captures| captures.get(1))
+          .ok_or(LoadError::FormatError)
+          .and_then(|m| String::from_utf8(m.as_bytes().to_vec()).map_err(|_| LoadError::FormatError))
+      })
+  } else {
+    res
+  }
+}
+
+/**
+ * Seconds since the Unix epoch, as timestamps are stored by the manager's own files.
+ */
+pub fn now() -> u64 {
+  SystemTime::now().duration_since(UNIX_EPOCH).map(|since| since.as_secs()).unwrap_or_default()
+}
+
+/**
+ * Runs `f` against the contents of the JSON file at `path` while holding `lock`, writing them back if `f` returns true.
+ * A missing or unreadable file reads as the default value.
+ */
+pub(crate) async fn with_json_file<D, T, F>(path: PathBuf, lock: &'static Mutex<()>, f: F) -> io::Result<T>
+where
+  D: Default + Serialize + DeserializeOwned,
+  T: Send + 'static,
+  F: FnOnce(&mut D) -> (T, bool) + Send + 'static,
+{
+  task::spawn_blocking(move || {
+    let _lock = lock.lock().expect("Lock JSON file");
+
+    let mut contents: D = std::fs::read_to_string(&path).ok()
+      .and_then(|json| serde_json::from_str(&json).ok())
+      .unwrap_or_default();
+    let (res, changed) = f(&mut contents);
+    if changed {
+      write_json(&path, &contents)?;
+    }
+
+    Ok(res)
+  }).await.expect("Run blocking JSON file access")
+}
+
+/**
+ * Saves `contents` as JSON to `path` in the background, see `write_json`.
+ */
+pub(crate) async fn save_json<D: Serialize + Send + 'static>(path: PathBuf, contents: D) -> Result<(), SaveError> {
+  task::spawn_blocking(move || write_json(&path, &contents))
+    .await
+    .expect("Run blocking JSON file write")
+    .map_err(|err| match err.kind() {
+      io::ErrorKind::InvalidData => SaveError::FormatError,
+      _ => SaveError::WriteError
+    })
+}
+
+/**
+ * Writes `contents` as JSON to a temporary file beside `path`, then moves it over `path`, so a crash never leaves it half written.
+ */
+pub(crate) fn write_json<D: Serialize>(path: &Path, contents: &D) -> io::Result<()> {
+  let json = serde_json::to_string_pretty(contents)
+    .map_err(|err| io::Error::new(io::ErrorKind::InvalidData, err))?;
+
+  let temp_path = path.with_extension("json.tmp");
+  let mut file = std::fs::File::create(&temp_path)?;
+  io::Write::write_all(&mut file, json.as_bytes())?;
+  file.sync_all()?;
+
+  std::fs::rename(temp_path, path)
+}
+
+/**
+ * Formats seconds since the Unix epoch as a UTC date and time, e.g. `2021-06-14 09:30 UTC`.
+ */
+pub fn format_timestamp(secs: u64) -> String {
+  let days = (secs / 86400) as i64;
+  let time = secs % 86400;
+
+  // Howard Hinnant's days-to-civil algorithm, so we don't need a whole date library to print a date
+  let z = days + 719468;
+  let era = z.div_euclid(146097);
+  let doe = z.rem_euclid(146097);
+  let yoe = (doe - doe / 1460 + doe / 36524 - doe / 146096) / 365;
+  let doy = doe - (365 * yoe + yoe / 4 - yoe / 100);
+  let mp = (5 * doy + 2) / 153;
+  let day = doy - (153 * mp + 2) / 5 + 1;
+  let month = if mp < 10 { mp + 3 } else { mp - 9 };
+  let year = yoe + era * 400 + if month <= 2 { 1 } else { 0 };
+
+  format!("{:04}-{:02}-{:02} {:02}:{:02} UTC", year, month, day, time / 3600, (time % 3600) / 60)
+}
+
+/**
+ * Splits the text of a spreadsheet into records of fields, as the game reads the `.csv` files mods ship.
+ * A field that starts with a quote runs until the closing quote, so it can hold commas, line breaks and `""` escaped quotes.
+ */
+pub(crate) fn parse_csv(text: &str) -> Vec<Vec<String>> {
+  let mut records = vec![];
+  let mut record = vec![];
+  let mut field = String::new();
+  let mut quoted = false;
+  let mut chars = text.trim_start_matches('\u{feff}').chars().peekable();
+
+  while let Some(c) = chars.next() {
+    match c {
+      '"' if quoted => {
+        if chars.peek() == Some(&'"') {
+          chars.next();
+          field.push('"');
+        } else {
+          quoted = false;
+        }
+      },
+      '"' if field.trim().is_empty() => {
+        field.clear();
+        quoted = true;
+      },
+      ',' if !quoted => record.push(std::mem::take(&mut field)),
+      '\r' if !quoted => {},
+      '\n' if !quoted => {
+        record.push(std::mem::take(&mut field));
+        records.push(std::mem::take(&mut record));
+      },
+      c => field.push(c)
+    }
+  }
+  if !field.is_empty() || !record.is_empty() {
+    record.push(field);
+    records.push(record);
+  }
+
+  records
+}
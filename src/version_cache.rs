@@ -0,0 +1,87 @@
+use std::{collections::HashMap, path::PathBuf, sync::Mutex};
+use serde::{Serialize, Deserialize};
+use lazy_static::lazy_static;
+
+use crate::config::config_dir;
+use crate::util::{now, with_json_file};
+
+const CACHE_FILE: &str = "version_cache.json";
+
+lazy_static! {
+  /**
+   * Held while the cache is read and written, so concurrent refreshes don't lose each other's entries.
+   */
+  static ref CACHE_LOCK: Mutex<()> = Mutex::new(());
+}
+
+/**
+ * The last remote Version Checker file fetched for a mod. It lets update statuses be shown before, or without, checking
+ * again, and lets the next check ask the host whether the file has changed at all.
+ */
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CachedVersion {
+  pub url: String,
+  /**
+   * The file as it was served. It's parsed again when read, so the cache doesn't depend on how versions are stored.
+   */
+  pub body: String,
+  pub etag: Option<String>,
+  pub last_modified: Option<String>,
+  /**
+   * Seconds since the Unix epoch.
+   */
+  pub fetched_at: u64,
+}
+
+pub async fn cache_path(try_make: bool) -> PathBuf {
+  config_dir(try_make).await.join(CACHE_FILE)
+}
+
+impl CachedVersion {
+  /**
+   * The Version Checker file at `url`, as just fetched.
+   */
+  pub fn new(url: String, body: String, etag: Option<String>, last_modified: Option<String>) -> Self {
+    CachedVersion {
+      url,
+      body,
+      etag,
+      last_modified,
+      fetched_at: now(),
+    }
+  }
+
+  /**
+   * The same file, found to still be current.
+   */
+  pub fn refreshed(self) -> Self {
+    CachedVersion {
+      fetched_at: now(),
+      ..self
+    }
+  }
+}
+
+/**
+ * Every cached Version Checker file, by mod ID. Read once per refresh, with `save` writing back what the refresh fetched.
+ */
+pub async fn load() -> HashMap<String, CachedVersion> {
+  with_json_file(cache_path(false).await, &CACHE_LOCK, |entries: &mut HashMap<String, CachedVersion>| (entries.clone(), false)).await
+    .unwrap_or_default()
+}
+
+/**
+ * Saves the given Version Checker files, by mod ID, replacing whatever was cached for those mods and keeping the rest.
+ * The cache only saves requests, so failing to save it isn't an error.
+ */
+pub async fn save(updates: HashMap<String, CachedVersion>) {
+  if updates.is_empty() {
+    return
+  }
+
+  let _ = with_json_file(cache_path(true).await, &CACHE_LOCK, move |entries: &mut HashMap<String, CachedVersion>| {
+    entries.extend(updates);
+
+    ((), true)
+  }).await;
+}
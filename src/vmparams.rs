@@ -4,7 +4,7 @@ use std::iter::Peekable;
 use std::path::PathBuf;
 use if_chain::if_chain;
 
-use crate::gui::{LoadError, SaveError};
+use crate::{LoadError, SaveError};
 
 #[derive(Debug, Clone)]
 pub struct VMParams {